@@ -0,0 +1,134 @@
+use crate::cli::Easing;
+use std::time::Duration;
+use tokio::time::{interval, Interval, MissedTickBehavior};
+
+impl Easing {
+    /// Maps linear progress in `0.0..=1.0` onto the easing curve.
+    ///
+    /// Every curve returns exactly `0.0` at the start and `1.0` at the end.
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+                }
+            }
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::CubicInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+        }
+    }
+}
+
+/// Interpolates between two values, returning exactly `to` when `t` is `1.0`.
+pub fn lerp(from: f64, to: f64, t: f64) -> f64 {
+    from * (1.0 - t) + to * t
+}
+
+/// Paces an animation at a fixed frame rate.
+///
+/// Each call to [`Timeline::next_frame`] waits for the next tick and yields
+/// the eased progress of that frame. The final frame always yields `1.0`, so
+/// animations land exactly on their target.
+pub struct Timeline {
+    easing: Easing,
+    frames: u32,
+    current: u32,
+    ticker: Interval,
+}
+
+impl Timeline {
+    pub fn new(duration: Duration, fps: u32, easing: Easing) -> Self {
+        let fps = fps.max(1);
+        let frames = (duration.as_secs_f64() * fps as f64).ceil().max(1.0) as u32;
+        let mut ticker = interval(duration.max(Duration::from_millis(1)) / frames);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        Self {
+            easing,
+            frames,
+            current: 0,
+            ticker,
+        }
+    }
+
+    /// Waits for the next frame and returns its eased progress, or `None`
+    /// once the animation has finished.
+    pub async fn next_frame(&mut self) -> Option<f64> {
+        if self.current >= self.frames {
+            return None;
+        }
+        // The first tick of a tokio interval completes immediately, so skip
+        // it to make frame one land one interval after the start.
+        if self.current == 0 {
+            self.ticker.tick().await;
+        }
+        self.ticker.tick().await;
+        self.current += 1;
+
+        if self.current == self.frames {
+            Some(1.0)
+        } else {
+            Some(self.easing.apply(self.current as f64 / self.frames as f64))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lerp, Timeline};
+    use crate::cli::Easing;
+    use std::time::Duration;
+
+    #[test]
+    fn test_easing_endpoints() {
+        for easing in [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+            Easing::CubicIn,
+            Easing::CubicOut,
+            Easing::CubicInOut,
+        ] {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert_eq!(easing.apply(1.0), 1.0);
+            assert!((0.0..=1.0).contains(&easing.apply(0.3)));
+        }
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+        assert!(Easing::EaseIn.apply(0.25) < 0.25);
+        assert!(Easing::CubicOut.apply(0.25) > 0.25);
+    }
+
+    #[test]
+    fn test_lerp_lands_on_target() {
+        assert_eq!(lerp(0.1, 0.7, 1.0), 0.7);
+        assert_eq!(lerp(-30.0, 6.0, 0.0), -30.0);
+        assert_eq!(lerp(0.0, 10.0, 0.5), 5.0);
+    }
+
+    #[tokio::test]
+    async fn test_timeline_frames() {
+        let mut timeline = Timeline::new(Duration::from_millis(100), 30, Easing::Linear);
+        let mut progress = Vec::new();
+        while let Some(t) = timeline.next_frame().await {
+            progress.push(t);
+        }
+
+        assert_eq!(progress.len(), 3);
+        assert_eq!(progress.last(), Some(&1.0));
+        assert!(progress.windows(2).all(|w| w[0] < w[1]));
+    }
+}
@@ -1,4 +1,5 @@
-use clap::{Parser, Subcommand};
+use clap::{ArgGroup, Args, Parser, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::str::FromStr;
 use url::Url;
@@ -109,8 +110,26 @@ pub enum Recording {
 pub enum Scene {
     Current,
     Switch {
-        scene_name: String,
+        #[clap(required_unless_present = "index", conflicts_with = "index")]
+        scene_name: Option<String>,
+        /// Switch to the scene at this position in the scene list (top = 1)
+        #[clap(long, value_parser = clap::value_parser!(u32).range(1..))]
+        index: Option<u32>,
+        /// Use this transition for this switch only
+        #[clap(long)]
+        transition: Option<String>,
+        /// Use this transition duration (ms) for this switch only
+        #[clap(long)]
+        duration: Option<u64>,
     },
+    /// Switch to the next scene in the scene list
+    Next(SceneCycle),
+    /// Switch to the previous scene in the scene list
+    Prev(SceneCycle),
+    /// Return to the scene that was live before the last obs-cmd switch
+    Back,
+    /// Cycle the program scene through a list of scenes on a timer
+    Rotate(SceneRotation),
     List,
     Create {
         scene_name: String,
@@ -122,6 +141,17 @@ pub enum Scene {
         scene_name: String,
         new_name: String,
     },
+    /// Copy a scene with all of its items into a new scene
+    Clone {
+        /// Scene to copy
+        scene_name: String,
+        /// Name of the new scene
+        new_name: String,
+        /// Give the new scene its own copies of the inputs (with their
+        /// settings and filters) instead of sharing them
+        #[clap(long)]
+        deep: bool,
+    },
     // Transition controls
     TransitionList,
     TransitionCurrent,
@@ -132,6 +162,9 @@ pub enum Scene {
         duration_ms: u64,
     },
     TransitionTrigger,
+    /// Get, set or clear the transition override of a scene
+    #[clap(subcommand)]
+    TransitionOverride(TransitionOverride),
     // Studio mode controls
     StudioModeStatus,
     StudioModeEnable,
@@ -143,6 +176,121 @@ pub enum Scene {
     PreviewSet {
         scene_name: String,
     },
+    // Studio mode workflow
+    /// Put a scene in preview, enabling studio mode if needed
+    Stage {
+        scene_name: String,
+    },
+    /// Transition the preview scene to program
+    Take {
+        /// Use this transition for this take only
+        #[clap(long)]
+        transition: Option<String>,
+        /// Use this transition duration (ms) for this take only
+        #[clap(long)]
+        duration: Option<u64>,
+        /// Wait until the transition has finished before returning
+        #[clap(long)]
+        wait: bool,
+    },
+    /// Exchange the program and preview scenes
+    Swap,
+}
+
+#[derive(Args, Clone, Debug, PartialEq)]
+pub struct SceneRotation {
+    /// Comma-separated scenes to rotate through, in order
+    #[clap(long, value_delimiter = ',', required = true)]
+    pub scenes: Vec<String>,
+    /// How long each scene stays live, e.g. "20s" or "1m"
+    #[clap(long, value_parser = parse_interval, default_value = "20s")]
+    pub interval: std::time::Duration,
+    /// Shuffle the order of the scenes on every round
+    #[clap(long)]
+    pub shuffle: bool,
+    /// Transition to use while rotating
+    #[clap(long)]
+    pub transition: Option<String>,
+    /// Stop rotating when this event occurs
+    #[clap(long, value_enum)]
+    pub until_event: Option<RotationStop>,
+}
+
+/// Events that end a scene rotation.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum RotationStop {
+    /// The stream output stopped
+    StreamStopped,
+    /// The record output stopped
+    RecordStopped,
+}
+
+#[derive(Args, Clone, Debug, PartialEq)]
+pub struct SceneCycle {
+    /// Continue from the other end of the scene list
+    #[clap(long)]
+    pub wrap: bool,
+    /// Skip scenes whose name matches this glob, e.g. "[nested] *" (repeatable)
+    #[clap(long = "skip-pattern")]
+    pub skip_patterns: Vec<String>,
+}
+
+#[derive(Subcommand, Clone, Debug, PartialEq)]
+pub enum TransitionOverride {
+    /// Show the transition override of a scene
+    Get { scene_name: String },
+    /// Override the transition used when switching to a scene
+    Set {
+        scene_name: String,
+        /// Name of the transition to use
+        #[clap(long, required_unless_present = "duration")]
+        transition: Option<String>,
+        /// Transition duration in milliseconds
+        #[clap(long)]
+        duration: Option<u64>,
+    },
+    /// Remove the transition override of a scene
+    Clear { scene_name: String },
+}
+
+#[derive(Subcommand, Clone, Debug, PartialEq)]
+pub enum Transition {
+    /// List the available transition kinds
+    Kinds,
+    /// Update the current transition's settings from a JSON object
+    Settings {
+        /// Settings as a JSON object, merged into the current settings
+        settings: String,
+        /// Replace all settings instead of merging
+        #[clap(long)]
+        replace: bool,
+    },
+    /// Get the cursor position of the running transition (0.0 to 1.0)
+    Cursor,
+    /// Move the studio mode T-bar, either once or as a timed slide
+    Tbar {
+        /// Position to move the T-bar to (0.0 to 1.0)
+        #[clap(value_parser = parse_unit_interval, required_unless_present = "to", conflicts_with = "to")]
+        position: Option<f64>,
+        /// Position to start a slide from
+        #[clap(long, value_parser = parse_unit_interval, default_value = "0", requires = "to")]
+        from: f64,
+        /// Position to slide the T-bar to
+        #[clap(long, value_parser = parse_unit_interval)]
+        to: Option<f64>,
+        /// Length of the slide, e.g. "3s"
+        #[clap(long, value_parser = parse_interval, default_value = "1s")]
+        duration: std::time::Duration,
+        /// Easing curve applied to the slide
+        #[clap(long, value_enum, default_value_t = Easing::Linear)]
+        easing: Easing,
+        /// Number of T-bar updates sent per second
+        #[clap(long, default_value_t = 30, value_parser = clap::value_parser!(u32).range(1..=120))]
+        fps: u32,
+        /// Release the T-bar after the final position, completing the transition
+        #[clap(long)]
+        release: bool,
+    },
 }
 
 #[derive(Subcommand, Clone, Debug)]
@@ -203,44 +351,110 @@ pub enum RecordDirectory {
 #[derive(Subcommand, Clone, Debug)]
 pub enum SceneItem {
     /// List all scene items in a scene
-    List { scene: String },
-    /// Create a new scene item from a source
+    List {
+        scene: String,
+        /// Also list the items inside groups
+        #[clap(long, short)]
+        recursive: bool,
+        /// Show kind, state, blend mode, transform and UUID of each item
+        #[clap(long, short)]
+        long: bool,
+        /// Also list the items of nested scenes
+        #[clap(long)]
+        expand_nested: bool,
+        /// Print the item tree as JSON
+        #[clap(long)]
+        json: bool,
+    },
+    /// Create a new scene item from a source.
+    ///
+    /// The item stays hidden until every option has been applied, and is
+    /// removed again if one of them fails.
     Create {
         scene: String,
         source: String,
         #[clap(long)]
         enabled: Option<bool>,
+        /// Create the item inside this group of the scene
+        #[clap(long)]
+        group: Option<String>,
+        #[clap(long, allow_hyphen_values = true)]
+        position_x: Option<f64>,
+        #[clap(long, allow_hyphen_values = true)]
+        position_y: Option<f64>,
+        #[clap(long)]
+        scale_x: Option<f64>,
+        #[clap(long)]
+        scale_y: Option<f64>,
+        #[clap(long, allow_hyphen_values = true)]
+        rotation: Option<f64>,
+        /// Point of the item that its position refers to
+        #[clap(long, value_enum)]
+        alignment: Option<ItemAlignment>,
+        /// Index position of the new item (0 is the bottom)
+        #[clap(long)]
+        index: Option<u32>,
+        /// Blend mode of the new item (the same values as set-blend-mode)
+        #[clap(long = "mode")]
+        blend_mode: Option<String>,
+        /// Lock the new item
+        #[clap(long)]
+        locked: bool,
     },
     /// Remove a scene item from a scene
     Remove { scene: String, source: String },
     /// Duplicate a scene item in a scene
     Duplicate { scene: String, source: String },
+    /// Copy a scene item to another scene, reusing the same source
+    Copy {
+        /// Scene the item is in
+        from_scene: String,
+        /// Path of the item to copy
+        source: String,
+        /// Scene to add the copy to
+        to_scene: String,
+        /// Keep the item's position, scale, rotation and crop instead of
+        /// placing the copy like a newly created item
+        #[clap(long)]
+        keep_transform: bool,
+    },
     /// Enable or disable a scene item
-    Enable { scene: String, source: String },
+    Enable {
+        #[clap(flatten)]
+        target: ItemSelector,
+        /// With --match, disable every other item in the scenes searched
+        #[clap(long, conflicts_with = "source")]
+        exclusive: bool,
+    },
     /// Disable a scene item
-    Disable { scene: String, source: String },
+    Disable(ItemSelector),
     /// Toggle a scene item's enabled state
-    Toggle { scene: String, source: String },
+    Toggle(ItemSelector),
     /// Lock a scene item
-    Lock { scene: String, source: String },
+    Lock(ItemSelector),
     /// Unlock a scene item
-    Unlock { scene: String, source: String },
+    Unlock(ItemSelector),
     /// Get transform info of a scene item
     GetTransform { scene: String, source: String },
-    /// Set transform info of a scene item
+    /// Set transform info of a scene item.
+    ///
+    /// Position, scale, rotation and bounds values are absolute (100),
+    /// relative to the current transform (+20, -15, *1.1), or a percentage of
+    /// the canvas (50%, positions and bounds only). Use =-20 for a negative
+    /// absolute value.
     SetTransform {
         scene: String,
         source: String,
-        #[clap(long)]
-        position_x: Option<f64>,
-        #[clap(long)]
-        position_y: Option<f64>,
-        #[clap(long)]
-        scale_x: Option<f64>,
-        #[clap(long)]
-        scale_y: Option<f64>,
-        #[clap(long)]
-        rotation: Option<f64>,
+        #[clap(long, value_parser = parse_transform_value, allow_hyphen_values = true)]
+        position_x: Option<TransformValue>,
+        #[clap(long, value_parser = parse_transform_value, allow_hyphen_values = true)]
+        position_y: Option<TransformValue>,
+        #[clap(long, value_parser = parse_transform_value, allow_hyphen_values = true)]
+        scale_x: Option<TransformValue>,
+        #[clap(long, value_parser = parse_transform_value, allow_hyphen_values = true)]
+        scale_y: Option<TransformValue>,
+        #[clap(long, value_parser = parse_transform_value, allow_hyphen_values = true)]
+        rotation: Option<TransformValue>,
         #[clap(long)]
         crop_left: Option<u32>,
         #[clap(long)]
@@ -249,6 +463,43 @@ pub enum SceneItem {
         crop_top: Option<u32>,
         #[clap(long)]
         crop_bottom: Option<u32>,
+        /// Point of the item that its position refers to
+        #[clap(long, value_enum)]
+        alignment: Option<ItemAlignment>,
+        /// How the item is fitted into its bounding box
+        #[clap(long, value_enum)]
+        bounds_type: Option<ItemBoundsType>,
+        /// Width of the bounding box
+        #[clap(long, value_parser = parse_transform_value, allow_hyphen_values = true)]
+        bounds_width: Option<TransformValue>,
+        /// Height of the bounding box
+        #[clap(long, value_parser = parse_transform_value, allow_hyphen_values = true)]
+        bounds_height: Option<TransformValue>,
+        /// Where the item sits inside its bounding box
+        #[clap(long, value_enum)]
+        bounds_alignment: Option<ItemAlignment>,
+        /// Place the item against this point of the canvas
+        #[clap(long, value_enum, conflicts_with_all = ["position_x", "position_y"])]
+        anchor: Option<ItemAlignment>,
+        /// Distance in pixels between the anchored item and the canvas edges
+        #[clap(long, requires = "anchor", default_value_t = 0.0)]
+        margin: f64,
+    },
+    /// Scale a scene item to fit the canvas, keeping its aspect ratio
+    Fit { scene: String, source: String },
+    /// Stretch a scene item to fill the canvas
+    Stretch { scene: String, source: String },
+    /// Center a scene item on the canvas without resizing it
+    Center { scene: String, source: String },
+    /// Smoothly move, scale, rotate or crop scene items to a target transform
+    Animate(ItemAnimation),
+    /// Arrange scene items in a grid, picture-in-picture or side by side,
+    /// or save and reapply named layouts
+    Layout {
+        /// Name of the scene
+        scene: String,
+        #[clap(subcommand)]
+        layout: Layout,
     },
     /// Get the index position of a scene item
     GetIndex { scene: String, source: String },
@@ -262,12 +513,234 @@ pub enum SceneItem {
     GetBlendMode { scene: String, source: String },
     /// Set the blend mode of a scene item
     SetBlendMode {
-        scene: String,
-        source: String,
+        #[clap(flatten)]
+        target: ItemSelector,
+        /// normal, additive, subtract, screen, multiply, lighten or darken
+        #[clap(long = "mode")]
         blend_mode: String,
     },
 }
 
+/// Selects one scene item by path, or every item whose source name matches
+/// a pattern, including items inside groups.
+#[derive(Args, Clone, Debug, PartialEq)]
+pub struct ItemSelector {
+    /// Scene containing the item(s)
+    #[clap(required_unless_present = "all_scenes")]
+    pub scene: Option<String>,
+    /// Scene item path
+    #[clap(required_unless_present = "pattern", conflicts_with = "pattern")]
+    pub source: Option<String>,
+    /// Select every item whose source name matches this glob pattern
+    #[clap(long = "match")]
+    pub pattern: Option<String>,
+    /// Treat the --match pattern as a regular expression
+    #[clap(long, conflicts_with = "source")]
+    pub regex: bool,
+    /// Search every scene instead of a single one
+    #[clap(long, conflicts_with = "source")]
+    pub all_scenes: bool,
+}
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum Filter {
+    /// Enable a filter
+    Enable(FilterTarget),
+    /// Disable a filter
+    Disable(FilterTarget),
+    /// Toggle a filter's enabled state
+    Toggle(FilterTarget),
+    /// Smoothly ramp a numeric filter setting to a target value
+    Animate(FilterAnimation),
+    /// Apply or save named filter chains from the obs-cmd config file
+    #[clap(subcommand)]
+    Preset(FilterPreset),
+}
+
+#[derive(Subcommand, Clone, Debug, PartialEq)]
+pub enum FilterPreset {
+    /// Apply a named preset's filter chain to a source
+    Apply {
+        /// Name of the source to configure
+        source: String,
+        /// Name of the preset in the config file
+        preset: String,
+        /// Also remove filters on the source that are not part of the preset
+        #[clap(long)]
+        replace: bool,
+    },
+    /// Save a source's current filter chain as a named preset
+    Save {
+        /// Name of the source to capture
+        source: String,
+        /// Name to store the preset under (overwrites an existing preset)
+        name: String,
+    },
+}
+
+/// Selects one filter by name, or many filters by glob pattern.
+///
+/// Glob patterns support `*` (any run of characters) and `?` (any single
+/// character). Sources are matched against every input and scene.
+#[derive(Args, Clone, Debug, PartialEq)]
+pub struct FilterTarget {
+    /// Name of the source the filter belongs to
+    #[clap(
+        required_unless_present = "filter_glob",
+        conflicts_with_all = ["source_glob", "filter_glob"]
+    )]
+    pub source: Option<String>,
+    /// Name of the filter
+    #[clap(required_unless_present = "filter_glob")]
+    pub filter: Option<String>,
+    /// Glob pattern for source names (defaults to all sources)
+    #[clap(long, requires = "filter_glob")]
+    pub source_glob: Option<String>,
+    /// Glob pattern for filter names
+    #[clap(long)]
+    pub filter_glob: Option<String>,
+    /// List matching filters without changing them
+    #[clap(long)]
+    pub dry_run: bool,
+}
+
+/// A numeric filter setting ramp, sent as one settings update per frame.
+#[derive(Args, Clone, Debug, PartialEq)]
+pub struct FilterAnimation {
+    /// Name of the source the filter belongs to
+    pub source: String,
+    /// Name of the filter
+    pub filter: String,
+    /// Name of the numeric setting to animate (e.g. "opacity")
+    pub setting: String,
+    /// Final value of the setting
+    #[clap(long, allow_hyphen_values = true)]
+    pub to: f64,
+    /// Starting value (defaults to the setting's current value)
+    #[clap(long, allow_hyphen_values = true)]
+    pub from: Option<f64>,
+    /// Length of the animation, e.g. "2s", "1.5s" or "800ms"
+    #[clap(long, value_parser = parse_interval, default_value = "1s")]
+    pub duration: std::time::Duration,
+    /// Easing curve applied to the animation
+    #[clap(long, value_enum, default_value_t = Easing::Linear)]
+    pub easing: Easing,
+    /// Number of updates sent per second
+    #[clap(long, default_value_t = 30, value_parser = clap::value_parser!(u32).range(1..=120))]
+    pub fps: u32,
+    /// Value to snap to when interrupted with Ctrl-C
+    #[clap(long, value_enum, default_value_t = AbortSnap::End)]
+    pub on_abort: AbortSnap,
+}
+
+/// Layouts computed from the canvas size, plus saved custom layouts.
+///
+/// Computed layouts scale each item to fit its cell without distortion,
+/// using the item's cropped source size.
+#[derive(Subcommand, Clone, Debug, PartialEq)]
+pub enum Layout {
+    /// Arrange items in an evenly sized grid, filled row by row
+    Grid {
+        /// Paths of the items, e.g. "A,B,C,D"
+        #[clap(long, value_delimiter = ',', required = true)]
+        items: Vec<String>,
+        /// Number of columns (defaults to a square-ish grid)
+        #[clap(long, value_parser = clap::value_parser!(u32).range(1..))]
+        columns: Option<u32>,
+        /// Space between cells in pixels
+        #[clap(long, default_value_t = 0.0)]
+        gap: f64,
+        /// Space between the grid and the canvas edges in pixels
+        #[clap(long, default_value_t = 0.0)]
+        margin: f64,
+    },
+    /// Show one item full screen with a smaller inset in a corner
+    Pip {
+        /// Path of the full screen item
+        #[clap(long)]
+        main: String,
+        /// Path of the inset item
+        #[clap(long)]
+        inset: String,
+        /// Corner (or edge) of the canvas the inset sits in
+        #[clap(long, value_enum, default_value_t = ItemAlignment::BottomRight)]
+        corner: ItemAlignment,
+        /// Size of the inset as a percentage of the canvas, e.g. "25%"
+        #[clap(long, value_parser = parse_percent, default_value = "25%")]
+        size: f64,
+        /// Space between the inset and the canvas edges in pixels
+        #[clap(long, default_value_t = 32.0)]
+        margin: f64,
+    },
+    /// Show two items next to each other
+    SideBySide {
+        /// Path of the left item
+        left: String,
+        /// Path of the right item
+        right: String,
+        /// Space between the items in pixels
+        #[clap(long, default_value_t = 0.0)]
+        gap: f64,
+        /// Space between the items and the canvas edges in pixels
+        #[clap(long, default_value_t = 0.0)]
+        margin: f64,
+    },
+    /// Save the current transforms of items as a named layout
+    Save {
+        /// Name to store the layout under (overwrites an existing layout)
+        name: String,
+        /// Paths of the items to save (defaults to every top-level item)
+        #[clap(long, value_delimiter = ',')]
+        items: Vec<String>,
+    },
+    /// Reapply a saved layout to the items with the same paths
+    Apply {
+        /// Name of the saved layout
+        name: String,
+    },
+}
+
+/// A scene item transform animation, sent as one transform update per item
+/// per frame. All items move in lockstep from their current transforms.
+#[derive(Args, Clone, Debug, PartialEq)]
+#[clap(group(
+    ArgGroup::new("targets")
+        .args(["to_position", "to_scale", "to_rotation", "to_crop"])
+        .required(true)
+        .multiple(true)
+))]
+pub struct ItemAnimation {
+    /// Name of the scene
+    pub scene: String,
+    /// Paths of the scene items to animate
+    #[clap(required = true)]
+    pub items: Vec<String>,
+    /// Final position as "x,y"
+    #[clap(long, value_parser = parse_point, allow_hyphen_values = true)]
+    pub to_position: Option<(f64, f64)>,
+    /// Final scale as "s" or "x,y"
+    #[clap(long, value_parser = parse_scale)]
+    pub to_scale: Option<(f64, f64)>,
+    /// Final rotation in degrees
+    #[clap(long, allow_hyphen_values = true)]
+    pub to_rotation: Option<f64>,
+    /// Final crop as "left,top,right,bottom"
+    #[clap(long, value_parser = parse_crop)]
+    pub to_crop: Option<[u32; 4]>,
+    /// Length of the animation, e.g. "2s", "1.5s" or "800ms"
+    #[clap(long, value_parser = parse_interval, default_value = "1s")]
+    pub duration: std::time::Duration,
+    /// Easing curve applied to the animation
+    #[clap(long, value_enum, default_value_t = Easing::Linear)]
+    pub easing: Easing,
+    /// Number of updates sent per second
+    #[clap(long, default_value_t = 30, value_parser = clap::value_parser!(u32).range(1..=120))]
+    pub fps: u32,
+    /// Transform to snap to when interrupted with Ctrl-C
+    #[clap(long, value_enum, default_value_t = AbortSnap::End)]
+    pub on_abort: AbortSnap,
+}
+
 /// Command-line interface for obs-cmd.
 ///
 /// This struct defines the main CLI interface using clap for parsing.
@@ -314,6 +787,9 @@ pub enum Commands {
     #[clap(subcommand)]
     Scene(Scene),
 
+    #[clap(subcommand)]
+    Transition(Transition),
+
     #[clap(subcommand)]
     SceneCollection(SceneCollection),
 
@@ -353,20 +829,64 @@ pub enum Commands {
         compression_quality: Option<i32>,
     },
 
-    Audio {
-        command: String,
-        device: String,
-    },
+    #[clap(subcommand)]
+    Audio(Audio),
 
-    Filter {
-        command: String,
-        source: String,
-        filter: String,
-    },
+    #[clap(subcommand)]
+    Filter(Filter),
 
+    /// Manage scene items, addressed by path: "Source", "Group/Sub/Source",
+    /// "#12" (scene item ID) or "Cam@2" (second "Cam" from the top). Write a
+    /// literal "/" as "\/" or put the name in double quotes
     #[clap(subcommand)]
     SceneItem(SceneItem),
 
+    /// Capture the live OBS state as versioned JSON
+    #[clap(subcommand)]
+    Snapshot(Snapshot),
+
+    /// Reconcile OBS with a YAML or JSON state file
+    Apply {
+        /// State file in the format written by `snapshot export`
+        file: PathBuf,
+
+        /// Apply the plan instead of only printing it
+        #[clap(long, short)]
+        yes: bool,
+
+        /// Also remove scenes, inputs, items and filters the file does not declare
+        #[clap(long)]
+        prune: bool,
+    },
+
+    /// Compare OBS with a state file, or two OBS instances
+    Diff(DiffArgs),
+
+    /// Check an OBS scene collection file for problems, without connecting
+    Lint {
+        /// Scene collection JSON, as found under `basic/scenes/` in the OBS
+        /// configuration directory
+        file: PathBuf,
+    },
+
+    /// Print which scenes and groups show which sources, as a graph
+    Graph {
+        /// Output format
+        #[clap(long, value_enum, default_value_t = GraphFormat::Dot)]
+        format: GraphFormat,
+    },
+
+    /// List inputs that no scene or group shows. Desktop Audio and Mic/Aux
+    /// inputs are never listed
+    Orphans {
+        /// Remove the listed inputs
+        #[clap(long)]
+        remove: bool,
+    },
+
+    /// Rename inputs and scenes with a sed-style expression
+    Rename(RenameArgs),
+
     ListHotkeys,
 
     TriggerHotkey {
@@ -397,6 +917,112 @@ pub enum Commands {
     },
 }
 
+/// Which sources to rename, and how.
+#[derive(Args, Clone, Debug, PartialEq)]
+#[clap(group(
+    ArgGroup::new("sources")
+        .args(["inputs", "scenes"])
+        .required(true)
+        .multiple(true)
+))]
+pub struct RenameArgs {
+    /// Substitution such as 's/^Cam (\d)/Camera-$1/'. Flags: g (every
+    /// match) and i (ignore case)
+    pub expression: String,
+
+    /// Rename inputs
+    #[clap(long)]
+    pub inputs: bool,
+
+    /// Rename scenes
+    #[clap(long)]
+    pub scenes: bool,
+
+    /// Only print the renames
+    #[clap(long)]
+    pub dry_run: bool,
+}
+
+/// Output formats of the `graph` command.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum GraphFormat {
+    /// Graphviz DOT
+    Dot,
+    /// Mermaid flowchart
+    Mermaid,
+}
+
+#[derive(Subcommand, Clone, Debug, PartialEq)]
+pub enum Audio {
+    Mute {
+        device: String,
+    },
+    Unmute {
+        device: String,
+    },
+    Toggle {
+        device: String,
+    },
+    Status {
+        device: String,
+    },
+    /// Show the volume of an input in dB, percent and as a multiplier, or
+    /// change it
+    Volume {
+        device: String,
+        /// New volume: -6dB, 75% or 0.5, or a step: +3dB, 3dB-, +10%, -10%
+        #[clap(value_parser = parse_volume_value, allow_hyphen_values = true)]
+        value: Option<VolumeValue>,
+    },
+    /// Show volume, mute, balance, sync offset, monitoring and tracks of
+    /// every input with audio
+    Mixer {
+        /// Redraw the table whenever a mixer setting changes, until Ctrl-C
+        #[clap(long)]
+        watch: bool,
+    },
+}
+
+#[derive(Subcommand, Clone, Debug, PartialEq)]
+pub enum Snapshot {
+    /// Write every scene, item, input, filter, the current transition and the
+    /// video settings as JSON (to stdout unless --output is given)
+    Export {
+        /// File to write the snapshot to
+        #[clap(long, short)]
+        output: Option<PathBuf>,
+    },
+}
+
+/// The two states to compare, in order: the state file, then the targets,
+/// then the OBS instance obs-cmd is connected to when fewer than two were
+/// given. The first state is the reference.
+#[derive(Args, Clone, Debug, PartialEq)]
+pub struct DiffArgs {
+    /// State file (YAML or JSON) to compare with
+    pub file: Option<PathBuf>,
+
+    /// OBS instance to compare: a name from the "targets" section of the
+    /// config file or an obsws:// URL. Can be given twice
+    #[clap(long = "target", value_name = "TARGET")]
+    pub targets: Vec<String>,
+
+    /// Print the differences as JSON
+    #[clap(long)]
+    pub json: bool,
+
+    /// Exit with an error when the states differ
+    #[clap(long)]
+    pub exit_code: bool,
+}
+
+impl DiffArgs {
+    /// Whether one side of the diff is the default OBS connection
+    pub fn needs_default_connection(&self) -> bool {
+        usize::from(self.file.is_some()) + self.targets.len() == 1
+    }
+}
+
 #[derive(Subcommand, Clone, Debug)]
 pub enum MediaInput {
     /// Sets the cursor of the media input
@@ -556,6 +1182,278 @@ pub enum MuteAction {
     Status,
 }
 
+/// Easing curves for animated commands.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+}
+
+/// Anchor point of a scene item, or of the item inside its bounding box.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ItemAlignment {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+/// How a scene item is sized into its bounding box.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ItemBoundsType {
+    /// No bounding box
+    None,
+    /// Stretch to the bounds, ignoring the aspect ratio
+    Stretch,
+    /// Scale to fit inside the bounds
+    ScaleInner,
+    /// Scale to cover the bounds
+    ScaleOuter,
+    /// Scale to the width of the bounds
+    ScaleToWidth,
+    /// Scale to the height of the bounds
+    ScaleToHeight,
+    /// Only shrink items larger than the bounds
+    MaxOnly,
+}
+
+/// A scene item transform value, resolved against the current transform
+/// and the canvas size.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TransformValue {
+    /// Replace the value ("100" or "=-20")
+    Absolute(f64),
+    /// Add to the current value ("+20" or "-15")
+    Offset(f64),
+    /// Multiply the current value ("*1.1")
+    Factor(f64),
+    /// Percentage of the canvas width or height ("50%")
+    Percent(f64),
+}
+
+/// Parses a [`TransformValue`].
+///
+/// # Examples
+///
+/// * "100" -> Absolute(100)
+/// * "=-20" -> Absolute(-20)
+/// * "+20" -> Offset(20)
+/// * "-15" -> Offset(-15)
+/// * "*1.1" -> Factor(1.1)
+/// * "50%" -> Percent(50)
+pub fn parse_transform_value(s: &str) -> Result<TransformValue, String> {
+    let s = s.trim();
+    let number = |n: &str| {
+        n.trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|value| value.is_finite())
+            .ok_or_else(|| {
+                format!(
+                    "Invalid transform value '{}'. Use 100, =-20, +20, -15, *1.1 or 50%",
+                    s
+                )
+            })
+    };
+
+    if let Some(n) = s.strip_prefix('=') {
+        number(n).map(TransformValue::Absolute)
+    } else if let Some(n) = s.strip_prefix('*') {
+        number(n).map(TransformValue::Factor)
+    } else if let Some(n) = s.strip_suffix('%') {
+        number(n).map(TransformValue::Percent)
+    } else if s.starts_with('+') || s.starts_with('-') {
+        number(s).map(TransformValue::Offset)
+    } else {
+        number(s).map(TransformValue::Absolute)
+    }
+}
+
+/// An input volume, resolved against the current volume.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VolumeValue {
+    /// Multiplier, where 1.0 is 0 dB ("0.5")
+    Mul(f64),
+    /// Level in dB ("-6dB" or "=+3dB")
+    Db(f64),
+    /// Percentage of the multiplier, as the OBS mixer shows it ("75%")
+    Percent(f64),
+    /// Raise or lower by dB ("+3dB" or "3dB-")
+    DbStep(f64),
+    /// Raise or lower by percentage points ("+10%", "-10%" or "10%-")
+    PercentStep(f64),
+}
+
+/// Parses a [`VolumeValue`]. A leading "+" is a step, and so is a leading
+/// "-" on a percentage. dB levels are negative, so "-6dB" is a level and
+/// dB steps down are written with a trailing sign, as amixer does. A
+/// leading "=" always sets a level.
+///
+/// # Examples
+///
+/// * "0.5" -> Mul(0.5)
+/// * "-6dB" -> Db(-6)
+/// * "=+3dB" -> Db(3)
+/// * "75%" -> Percent(75)
+/// * "+3dB" -> DbStep(3)
+/// * "3dB-" -> DbStep(-3)
+/// * "-10%" -> PercentStep(-10)
+/// * "10%+" -> PercentStep(10)
+pub fn parse_volume_value(s: &str) -> Result<VolumeValue, String> {
+    let s = s.trim();
+    let error = || {
+        format!(
+            "Invalid volume '{}'. Use -6dB, 75% or 0.5, or a step like +3dB, 3dB-, +10% or -10%",
+            s
+        )
+    };
+
+    let (value, absolute) = match s.strip_prefix('=') {
+        Some(value) => (value, true),
+        None => (s, false),
+    };
+    let (value, trailing_sign) = match value.strip_suffix('+') {
+        Some(value) => (value, Some(1.0)),
+        None => match value.strip_suffix('-') {
+            Some(value) => (value, Some(-1.0)),
+            None => (value, None),
+        },
+    };
+    let (number, unit) = if value.to_lowercase().ends_with("db") {
+        (&value[..value.len() - 2], "dB")
+    } else if let Some(number) = value.strip_suffix('%') {
+        (number, "%")
+    } else {
+        (value, "")
+    };
+    let number = number.trim();
+    let n = number
+        .parse::<f64>()
+        .ok()
+        .filter(|n| n.is_finite())
+        .ok_or_else(error)?;
+    let signed = number.starts_with('+') || number.starts_with('-');
+
+    match (unit, trailing_sign) {
+        (_, Some(_)) if absolute || signed => Err(error()),
+        ("dB", Some(sign)) => Ok(VolumeValue::DbStep(sign * n)),
+        ("%", Some(sign)) => Ok(VolumeValue::PercentStep(sign * n)),
+        ("dB", None) if !absolute && number.starts_with('+') => Ok(VolumeValue::DbStep(n)),
+        ("dB", None) => Ok(VolumeValue::Db(n)),
+        ("%", None) if !absolute && signed => Ok(VolumeValue::PercentStep(n)),
+        ("%", None) => Ok(VolumeValue::Percent(n)),
+        // Multipliers have no steps
+        (_, Some(_)) => Err(error()),
+        (_, None) if signed && !absolute => Err(error()),
+        (_, None) => Ok(VolumeValue::Mul(n)),
+    }
+}
+
+/// Parses a percentage given as "25%" or "25"
+pub fn parse_percent(s: &str) -> Result<f64, String> {
+    let s = s.trim();
+    s.strip_suffix('%')
+        .unwrap_or(s)
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|percent| *percent > 0.0 && *percent <= 100.0)
+        .ok_or_else(|| format!("Invalid percentage '{}'. Expected a value like 25%", s))
+}
+
+/// Parses comma-separated numbers, e.g. "100,200"
+fn parse_numbers<T: std::str::FromStr>(s: &str, expected: &str) -> Result<Vec<T>, String> {
+    s.split(',')
+        .map(|part| part.trim().parse::<T>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| format!("Invalid value '{}'. Expected {}", s, expected))
+}
+
+/// Parses a point given as "x,y"
+pub fn parse_point(s: &str) -> Result<(f64, f64), String> {
+    match parse_numbers::<f64>(s, "x,y")?[..] {
+        [x, y] => Ok((x, y)),
+        _ => Err(format!("Invalid value '{}'. Expected x,y", s)),
+    }
+}
+
+/// Parses a scale given as "s" (both axes) or "x,y"
+pub fn parse_scale(s: &str) -> Result<(f64, f64), String> {
+    match parse_numbers::<f64>(s, "s or x,y")?[..] {
+        [scale] => Ok((scale, scale)),
+        [x, y] => Ok((x, y)),
+        _ => Err(format!("Invalid value '{}'. Expected s or x,y", s)),
+    }
+}
+
+/// Parses a crop given as "left,top,right,bottom"
+pub fn parse_crop(s: &str) -> Result<[u32; 4], String> {
+    parse_numbers::<u32>(s, "left,top,right,bottom")?
+        .try_into()
+        .map_err(|_| format!("Invalid value '{}'. Expected left,top,right,bottom", s))
+}
+
+/// Where an interrupted animation leaves its value.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum AbortSnap {
+    /// Restore the starting value
+    Start,
+    /// Jump to the target value
+    End,
+}
+
+/// Parses short interval strings such as "2s", "1.5s", "800ms" or "1m".
+///
+/// A bare number is interpreted as seconds.
+///
+/// # Examples
+///
+/// * "800ms" -> 800 milliseconds
+/// * "2s" -> 2 seconds
+/// * "1.5" -> 1.5 seconds
+/// * "1m" -> 1 minute
+pub fn parse_interval(s: &str) -> Result<std::time::Duration, String> {
+    let s = s.trim();
+    let (number, scale) = if let Some(n) = s.strip_suffix("ms") {
+        (n, 0.001)
+    } else if let Some(n) = s.strip_suffix('s') {
+        (n, 1.0)
+    } else if let Some(n) = s.strip_suffix('m') {
+        (n, 60.0)
+    } else if let Some(n) = s.strip_suffix('h') {
+        (n, 3600.0)
+    } else {
+        (s, 1.0)
+    };
+
+    match number.trim().parse::<f64>() {
+        Ok(value) if value.is_finite() && value >= 0.0 => {
+            Ok(std::time::Duration::from_secs_f64(value * scale))
+        }
+        _ => Err("Interval should be a number with an optional unit (ms, s, m, h)".into()),
+    }
+}
+
+/// Parses a fractional position between 0.0 and 1.0.
+fn parse_unit_interval(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(value) if (0.0..=1.0).contains(&value) => Ok(value),
+        _ => Err("Position should be a number between 0.0 and 1.0".into()),
+    }
+}
+
 /// Parses duration strings in [hh:]mm:ss format.
 ///
 /// This function converts human-readable time strings into Duration objects.
@@ -590,7 +1488,96 @@ fn parse_duration(s: &str) -> Result<time::Duration, String> {
 
 #[cfg(test)]
 mod tests {
-    use super::parse_duration;
+    use super::{
+        parse_crop, parse_duration, parse_interval, parse_percent, parse_point, parse_scale,
+        parse_transform_value, parse_volume_value, Cli, TransformValue, VolumeValue,
+    };
+    use clap::CommandFactory;
+    use std::time::Duration;
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(parse_interval("800ms").unwrap(), Duration::from_millis(800));
+        assert_eq!(parse_interval("2s").unwrap(), Duration::from_secs(2));
+        assert_eq!(parse_interval("1.5s").unwrap(), Duration::from_millis(1500));
+        assert_eq!(parse_interval("3").unwrap(), Duration::from_secs(3));
+        assert_eq!(parse_interval("1m").unwrap(), Duration::from_secs(60));
+        assert!(parse_interval("fast").is_err());
+        assert!(parse_interval("-1s").is_err());
+    }
+
+    #[test]
+    fn test_parse_transform_value() {
+        assert_eq!(
+            parse_transform_value("100").unwrap(),
+            TransformValue::Absolute(100.0)
+        );
+        assert_eq!(
+            parse_transform_value("=-20").unwrap(),
+            TransformValue::Absolute(-20.0)
+        );
+        assert_eq!(
+            parse_transform_value("+20").unwrap(),
+            TransformValue::Offset(20.0)
+        );
+        assert_eq!(
+            parse_transform_value("-15").unwrap(),
+            TransformValue::Offset(-15.0)
+        );
+        assert_eq!(
+            parse_transform_value("*1.1").unwrap(),
+            TransformValue::Factor(1.1)
+        );
+        assert_eq!(
+            parse_transform_value("50%").unwrap(),
+            TransformValue::Percent(50.0)
+        );
+        assert!(parse_transform_value("*").is_err());
+        assert!(parse_transform_value("wide").is_err());
+    }
+
+    #[test]
+    fn test_parse_volume_value() {
+        for (text, value) in [
+            ("0.5", VolumeValue::Mul(0.5)),
+            ("-6dB", VolumeValue::Db(-6.0)),
+            ("0 db", VolumeValue::Db(0.0)),
+            ("=+3dB", VolumeValue::Db(3.0)),
+            ("75%", VolumeValue::Percent(75.0)),
+            ("+3dB", VolumeValue::DbStep(3.0)),
+            ("3dB-", VolumeValue::DbStep(-3.0)),
+            ("1.5dB+", VolumeValue::DbStep(1.5)),
+            ("-10%", VolumeValue::PercentStep(-10.0)),
+            ("+10%", VolumeValue::PercentStep(10.0)),
+            ("5%-", VolumeValue::PercentStep(-5.0)),
+        ] {
+            assert_eq!(parse_volume_value(text).unwrap(), value, "{}", text);
+        }
+        for invalid in ["loud", "dB", "+0.1", "-0.5", "0.5+", "+3dB-", "=3dB-", "%"] {
+            assert!(parse_volume_value(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_parse_animation_targets() {
+        assert_eq!(parse_point("100,-200").unwrap(), (100.0, -200.0));
+        assert!(parse_point("100").is_err());
+        assert_eq!(parse_scale("0.5").unwrap(), (0.5, 0.5));
+        assert_eq!(parse_scale("0.5, 2").unwrap(), (0.5, 2.0));
+        assert!(parse_scale("1,2,3").is_err());
+        assert_eq!(parse_crop("10,0,10,20").unwrap(), [10, 0, 10, 20]);
+        assert!(parse_crop("10,0").is_err());
+        assert!(parse_crop("-1,0,0,0").is_err());
+        assert_eq!(parse_percent("25%").unwrap(), 25.0);
+        assert_eq!(parse_percent("33.5").unwrap(), 33.5);
+        assert!(parse_percent("0%").is_err());
+        assert!(parse_percent("150%").is_err());
+    }
 
     #[test]
     fn test_parse_duration() {
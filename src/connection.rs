@@ -1,9 +1,17 @@
 #![allow(clippy::redundant_closure)]
 
+use crate::cli::ObsWebsocket;
 use crate::error::{ObsCmdError, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use futures_util::{SinkExt, StreamExt};
 use obws::Client;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
 use std::time::Duration;
+use tokio::net::TcpStream;
 use tokio::time::timeout;
+use tokio_websockets::{ClientBuilder, MaybeTlsStream, Message, WebSocketStream};
 
 /// Configuration for OBS WebSocket connection attempts.
 ///
@@ -130,3 +138,119 @@ pub async fn check_connection_health(client: &Client) -> Result<()> {
 
     Ok(())
 }
+
+/// Sends a single request to OBS over a short-lived connection of its own.
+///
+/// obws never serializes `None` fields, so requests that need an explicit
+/// `null` (removing a scene's transition override) are built here as JSON.
+/// Returns the request's `responseData`.
+pub async fn send_raw_request(
+    server: &ObsWebsocket,
+    request_type: &str,
+    request_data: Value,
+) -> Result<Value> {
+    let fail = |message: String| ObsCmdError::RequestFailed {
+        request_type: request_type.to_string(),
+        message,
+    };
+
+    let (mut socket, _) = timeout(
+        Duration::from_secs(10),
+        ClientBuilder::new()
+            .uri(&format!("ws://{}:{}", server.hostname, server.port))
+            .map_err(|e| fail(e.to_string()))?
+            .connect(),
+    )
+    .await
+    .map_err(|_| ObsCmdError::ConnectionTimeout { timeout: 10 })?
+    .map_err(|e| fail(e.to_string()))?;
+
+    let hello = next_op(&mut socket, 0).await.map_err(fail)?;
+    let mut identify = json!({ "rpcVersion": 1, "eventSubscriptions": 0 });
+    if let Some(auth) = hello.get("authentication") {
+        identify["authentication"] = json!(auth_response(
+            auth["challenge"].as_str().unwrap_or_default(),
+            auth["salt"].as_str().unwrap_or_default(),
+            server.password.as_deref().unwrap_or_default(),
+        ));
+    }
+    send_op(&mut socket, 1, identify).await.map_err(fail)?;
+    next_op(&mut socket, 2).await.map_err(fail)?;
+
+    send_op(
+        &mut socket,
+        6,
+        json!({
+            "requestType": request_type,
+            "requestId": "obs-cmd",
+            "requestData": request_data,
+        }),
+    )
+    .await
+    .map_err(fail)?;
+    let response = next_op(&mut socket, 7).await.map_err(fail)?;
+    let _ = socket.close().await;
+
+    let status = &response["requestStatus"];
+    if status["result"].as_bool() != Some(true) {
+        return Err(fail(format!(
+            "code {}: {}",
+            status["code"],
+            status["comment"].as_str().unwrap_or("no details")
+        )));
+    }
+    Ok(response["responseData"].clone())
+}
+
+async fn send_op(
+    socket: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+    op: u8,
+    data: Value,
+) -> std::result::Result<(), String> {
+    socket
+        .send(Message::text(json!({ "op": op, "d": data }).to_string()))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Waits for the next message with the given opcode and returns its data
+async fn next_op(
+    socket: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+    op: u64,
+) -> std::result::Result<Value, String> {
+    while let Some(message) = socket.next().await {
+        let message = message.map_err(|e| e.to_string())?;
+        let Some(text) = message.as_text() else {
+            continue;
+        };
+        let mut value: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        if value["op"].as_u64() == Some(op) {
+            return Ok(value["d"].take());
+        }
+    }
+    Err("connection closed by OBS".to_string())
+}
+
+/// Computes the obs-websocket authentication string for a password
+fn auth_response(challenge: &str, salt: &str, password: &str) -> String {
+    let secret = STANDARD.encode(Sha256::digest(format!("{}{}", password, salt)));
+    STANDARD.encode(Sha256::digest(format!("{}{}", secret, challenge)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::auth_response;
+
+    #[test]
+    fn test_auth_response_matches_protocol_example() {
+        // Example values from the obs-websocket protocol documentation
+        assert_eq!(
+            auth_response(
+                "+IxH4CnCiqpX1rM9scsNynZzbOe4KhDeYcTNS3PDaeY=",
+                "lM1GncleQOaCu9lT1yeUZhFYnqhsLLP1G5lAGo3ixaI=",
+                "supersecretpassword",
+            ),
+            "1Ct943GAT+6YQUUX47Ia/ncufilbe6+oD6lY+5kaCu4="
+        );
+    }
+}
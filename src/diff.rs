@@ -0,0 +1,331 @@
+use crate::plan::{item_changes, json, value_changes, Change};
+use crate::state::{FilterState, InputState, ItemState, ObsState, SceneState};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiffStatus {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// A scene, item, input, filter or setting group that differs between two
+/// states. Changed objects list their changed fields and the differing
+/// objects they contain.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DiffNode {
+    /// "scene", "item", "input", "filter", "transition" or "video"
+    pub object: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub status: DiffStatus,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub changes: Vec<Change>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<DiffNode>,
+}
+
+impl DiffNode {
+    fn new(object: &'static str, name: Option<&str>, status: DiffStatus) -> Self {
+        Self {
+            object,
+            name: name.map(String::from),
+            status,
+            changes: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    /// The node when it has any change, directly or below it
+    fn changed(self) -> Option<Self> {
+        (!self.changes.is_empty() || !self.children.is_empty()).then_some(self)
+    }
+
+    /// Number of added, removed and changed objects, counting changed
+    /// objects only for their own fields
+    pub fn count(&self) -> usize {
+        let own = usize::from(self.status != DiffStatus::Changed || !self.changes.is_empty());
+        own + self.children.iter().map(DiffNode::count).sum::<usize>()
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        let indent = "  ".repeat(depth);
+        let symbol = match self.status {
+            DiffStatus::Added => '+',
+            DiffStatus::Removed => '-',
+            DiffStatus::Changed => '~',
+        };
+        write!(f, "{}{} {}", indent, symbol, self.object)?;
+        if let Some(name) = &self.name {
+            write!(f, " \"{}\"", name)?;
+        }
+        writeln!(f)?;
+        for change in &self.changes {
+            writeln!(f, "{}    {}", indent, change)?;
+        }
+        for child in &self.children {
+            child.write(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DiffNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+/// Compares two states. Nodes describe how `to` differs from `from`.
+///
+/// Items are matched by source name in list order, with repeated sources
+/// named like item paths ("Camera@2"). Scenes, inputs and filters are
+/// matched by name.
+pub fn diff_states(from: &ObsState, to: &ObsState) -> Vec<DiffNode> {
+    let mut nodes = Vec::new();
+
+    if let (Some(a), Some(b)) = (&from.video, &to.video) {
+        let mut node = DiffNode::new("video", None, DiffStatus::Changed);
+        value_changes("", &json(b), &json(a), &mut node.changes);
+        nodes.extend(node.changed());
+    }
+    if let (Some(a), Some(b)) = (&from.transition, &to.transition) {
+        let mut node = DiffNode::new("transition", None, DiffStatus::Changed);
+        value_changes("", &json(b), &json(a), &mut node.changes);
+        nodes.extend(node.changed());
+    }
+
+    nodes.extend(diff_named(&from.scenes, &to.scenes, "scene", |a, b| {
+        let mut node = DiffNode::new("scene", Some(&a.name), DiffStatus::Changed);
+        diff_items(&a.items, &b.items, &mut node);
+        diff_filters(&a.filters, &b.filters, &mut node);
+        node.changed()
+    }));
+    nodes.extend(diff_named(&from.inputs, &to.inputs, "input", |a, b| {
+        let mut node = DiffNode::new("input", Some(&a.name), DiffStatus::Changed);
+        value_changes("", &input_fields(b), &input_fields(a), &mut node.changes);
+        diff_filters(&a.filters, &b.filters, &mut node);
+        node.changed()
+    }));
+    nodes
+}
+
+trait Named {
+    fn name(&self) -> &str;
+}
+
+impl Named for SceneState {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Named for InputState {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Named for FilterState {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Matches two lists by name: objects only in `to` are added, objects
+/// only in `from` removed, and `compare` reports on the rest
+fn diff_named<T: Named>(
+    from: &[T],
+    to: &[T],
+    object: &'static str,
+    compare: impl Fn(&T, &T) -> Option<DiffNode>,
+) -> Vec<DiffNode> {
+    let mut nodes = Vec::new();
+    for a in from {
+        match to.iter().find(|b| b.name() == a.name()) {
+            Some(b) => nodes.extend(compare(a, b)),
+            None => nodes.push(DiffNode::new(object, Some(a.name()), DiffStatus::Removed)),
+        }
+    }
+    for b in to {
+        if !from.iter().any(|a| a.name() == b.name()) {
+            nodes.push(DiffNode::new(object, Some(b.name()), DiffStatus::Added));
+        }
+    }
+    nodes
+}
+
+/// Input fields compared directly; filters are compared one by one
+fn input_fields(input: &InputState) -> Value {
+    let mut fields = json(input);
+    if let Value::Object(fields) = &mut fields {
+        fields.remove("name");
+        fields.remove("filters");
+    }
+    fields
+}
+
+fn diff_filters(from: &[FilterState], to: &[FilterState], parent: &mut DiffNode) {
+    parent
+        .children
+        .extend(diff_named(from, to, "filter", |a, b| {
+            let mut node = DiffNode::new("filter", Some(&a.name), DiffStatus::Changed);
+            value_changes("", &json(b), &json(a), &mut node.changes);
+            node.changed()
+        }));
+
+    let common = |filters: &[FilterState], other: &[FilterState]| -> Vec<Value> {
+        filters
+            .iter()
+            .filter(|filter| other.iter().any(|o| o.name == filter.name))
+            .map(|filter| Value::from(filter.name.as_str()))
+            .collect()
+    };
+    let (a, b) = (common(from, to), common(to, from));
+    if a != b {
+        parent.changes.push(Change {
+            field: "filter_order".to_string(),
+            from: Some(Value::Array(a)),
+            to: Some(Value::Array(b)),
+        });
+    }
+}
+
+fn diff_items(from: &[ItemState], to: &[ItemState], parent: &mut DiffNode) {
+    let (a_names, b_names) = (item_names(from), item_names(to));
+    let b_index: HashMap<&str, usize> = b_names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (name.as_str(), i))
+        .collect();
+
+    let mut common_a = Vec::new();
+    for (a, name) in from.iter().zip(&a_names) {
+        let Some(&i) = b_index.get(name.as_str()) else {
+            parent
+                .children
+                .push(DiffNode::new("item", Some(name), DiffStatus::Removed));
+            continue;
+        };
+        common_a.push(Value::from(name.as_str()));
+        let b = &to[i];
+        let mut node = DiffNode::new("item", Some(name), DiffStatus::Changed);
+        node.changes = item_changes(b, a);
+        diff_items(&a.items, &b.items, &mut node);
+        parent.children.extend(node.changed());
+    }
+
+    let mut common_b = Vec::new();
+    for name in &b_names {
+        if a_names.contains(name) {
+            common_b.push(Value::from(name.as_str()));
+        } else {
+            parent
+                .children
+                .push(DiffNode::new("item", Some(name), DiffStatus::Added));
+        }
+    }
+    if common_a != common_b {
+        parent.changes.push(Change {
+            field: "item_order".to_string(),
+            from: Some(Value::Array(common_a)),
+            to: Some(Value::Array(common_b)),
+        });
+    }
+}
+
+/// Item names with the repeat suffix item paths use: the second "Camera"
+/// of a list is "Camera@2"
+fn item_names(items: &[ItemState]) -> Vec<String> {
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    items
+        .iter()
+        .map(|item| {
+            let count = seen.entry(&item.source).or_default();
+            *count += 1;
+            if *count == 1 {
+                item.source.clone()
+            } else {
+                format!("{}@{}", item.source, count)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff_states, DiffStatus};
+    use crate::state::ObsState;
+    use serde_json::json;
+
+    fn state(value: serde_json::Value) -> ObsState {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_diff_states() {
+        let primary = state(json!({
+            "version": 1,
+            "transition": {"name": "Fade", "duration_ms": 300},
+            "scenes": [
+                {"name": "Live", "items": [
+                    {"source": "Camera", "id": 1},
+                    {"source": "Camera", "id": 2, "enabled": false},
+                    {"source": "Logo", "id": 3}
+                ]},
+                {"name": "Old"}
+            ],
+            "inputs": [
+                {"name": "Mic", "kind": "pulse_input_capture", "audio": {"volume_mul": 1.0},
+                 "filters": [{"name": "Gain", "kind": "gain_filter"}]}
+            ]
+        }));
+        let backup = state(json!({
+            "version": 1,
+            "transition": {"name": "Fade", "duration_ms": 500},
+            "scenes": [
+                {"name": "Live", "items": [
+                    {"source": "Logo", "id": 7},
+                    {"source": "Camera", "id": 8, "transform": {"position_x": 100.0}},
+                    {"source": "Camera", "id": 9, "enabled": false}
+                ]},
+                {"name": "New"}
+            ],
+            "inputs": [
+                {"name": "Mic", "kind": "pulse_input_capture", "audio": {"volume_mul": 0.5},
+                 "filters": [{"name": "Gain", "kind": "gain_filter", "enabled": false}]}
+            ]
+        }));
+
+        assert!(diff_states(&primary, &primary).is_empty());
+
+        let nodes = diff_states(&primary, &backup);
+        let text: String = nodes.iter().map(|node| node.to_string()).collect();
+        assert_eq!(
+            text,
+            "~ transition\n    duration_ms: 300 -> 500\n\
+             ~ scene \"Live\"\n    item_order: [\"Camera\",\"Camera@2\",\"Logo\"] -> [\"Logo\",\"Camera\",\"Camera@2\"]\n  \
+             ~ item \"Camera\"\n      transform.position_x: 0 -> 100\n\
+             - scene \"Old\"\n\
+             + scene \"New\"\n\
+             ~ input \"Mic\"\n    audio.volume_mul: 1 -> 0.5\n  \
+             ~ filter \"Gain\"\n      enabled: true -> false\n"
+        );
+        assert_eq!(nodes.iter().map(|node| node.count()).sum::<usize>(), 7);
+        assert_eq!(nodes[2].status, DiffStatus::Removed);
+
+        let written = serde_json::to_value(&nodes).unwrap();
+        assert_eq!(written[0]["object"], "transition");
+        assert!(written[0].get("name").is_none());
+        assert_eq!(written[0]["changes"][0]["to"], 500);
+        assert_eq!(written[3]["status"], "added");
+        assert_eq!(
+            written[1]["children"][0]["changes"][0]["field"],
+            "transform.position_x"
+        );
+    }
+}
@@ -19,11 +19,98 @@ pub enum ObsCmdError {
     #[error("Environment variable error: {0}. Set OBS_WEBSOCKET_URL environment variable")]
     EnvError(#[from] std::env::VarError),
 
-    #[error("Invalid audio command '{command}'. Valid commands are: mute, unmute, toggle, status")]
-    InvalidAudioCommand { command: String },
+    #[error("Setting '{setting}' of filter '{filter}' is not a number. Pass --from to set a starting value")]
+    NonNumericFilterSetting { filter: String, setting: String },
 
-    #[error("Invalid filter command '{command}'. Valid commands are: enable, disable, toggle")]
-    InvalidFilterCommand { command: String },
+    #[error("Filter preset '{name}' not found. Save one with: filter preset save <source> {name}")]
+    FilterPresetNotFound { name: String },
+
+    #[error("Layout '{name}' not found. Save one with: scene-item layout <scene> save {name}")]
+    LayoutNotFound { name: String },
+
+    #[error("Target '{name}' not found. Add it to the \"targets\" section of the config file or pass an obsws:// URL")]
+    TargetNotFound { name: String },
+
+    #[error("Config file {path:?} could not be used: {message}")]
+    ConfigError {
+        path: std::path::PathBuf,
+        message: String,
+    },
+
+    #[error("State file {path:?} could not be used: {message}")]
+    StateFileError {
+        path: std::path::PathBuf,
+        message: String,
+    },
+
+    #[error("diff compares two states: pass a state file and at most one --target, or two --target options")]
+    InvalidDiffSides,
+
+    #[error("States differ in {count} place(s)")]
+    StatesDiffer { count: usize },
+
+    #[error("Scene '{scene}' cannot be deep cloned: it contains group '{group}' and obs-websocket cannot create groups. Clone it without --deep or ungroup the items first")]
+    DeepCloneGroup { scene: String, group: String },
+
+    #[error("Cannot plan changes: {message}")]
+    InvalidStatePlan { message: String },
+
+    #[error("Scene collection {path:?} could not be read: {message}")]
+    CollectionFileError {
+        path: std::path::PathBuf,
+        message: String,
+    },
+
+    #[error("Found {count} error(s) in the scene collection")]
+    LintErrors { count: usize },
+
+    #[error("Request {request_type} failed: {message}")]
+    RequestFailed {
+        request_type: String,
+        message: String,
+    },
+
+    #[error("Invalid JSON: {0}")]
+    InvalidJson(#[from] serde_json::Error),
+
+    #[error("Scene index {index} is out of range. The scene list has {count} scene(s)")]
+    SceneIndexOutOfRange { index: u32, count: usize },
+
+    #[error("No earlier scene to go back to. Scene history is recorded by obs-cmd scene switches")]
+    NoSceneHistory,
+
+    #[error("None of the rotation scenes exist. Check the names passed to --scenes")]
+    NoRotationScenes,
+
+    #[error("Studio mode is not enabled. Stage a scene first with: scene stage <scene>")]
+    StudioModeNotEnabled,
+
+    #[error("Invalid scene item path '{path}': {message}. Escape a literal '/' as '\\/' or quote the name")]
+    InvalidItemPath { path: String, message: String },
+
+    #[error("Scene item '{item}' not found in '{container}'. List items with: scene-item list <scene> --recursive")]
+    SceneItemNotFound { item: String, container: String },
+
+    #[error("Scene item '{item}' is neither a group nor a scene, so it has no items")]
+    NotAnItemContainer { item: String },
+
+    #[error("Scene '{scene}' has no group named '{group}'")]
+    GroupNotInScene { scene: String, group: String },
+
+    #[error("Invalid scene item selection: {message}")]
+    InvalidItemSelector { message: String },
+
+    #[error("Invalid rename expression '{expression}': {message}")]
+    InvalidRenameExpression { expression: String, message: String },
+
+    #[error("Nothing was renamed, the new names clash:\n  {clashes}")]
+    RenameClashes { clashes: String },
+
+    #[error("Invalid regular expression: {0}")]
+    InvalidRegex(#[from] regex::Error),
+
+    #[error("Invalid value for --{field}: {message}")]
+    InvalidTransformValue { field: String, message: String },
 
     #[error("Invalid blend mode '{blend_mode}'. Valid modes are: normal, additive, subtract, screen, multiply, lighten, darken")]
     InvalidBlendMode { blend_mode: String },
@@ -0,0 +1,200 @@
+use crate::state::{ItemState, SceneState};
+use std::fmt::Write;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SourceNode {
+    Scene,
+    Group,
+    Input { kind: String },
+}
+
+/// Which scenes and groups show which sources.
+///
+/// Nodes are listed scenes first (top to bottom), then groups and inputs in
+/// the order they are first seen. A source shown several times by the same
+/// scene or group has a single edge.
+#[derive(Debug, Default, PartialEq)]
+pub struct SourceGraph {
+    pub nodes: Vec<(String, SourceNode)>,
+    pub edges: Vec<(String, String)>,
+}
+
+impl SourceGraph {
+    /// Builds the graph from scenes with their item trees and the
+    /// `(name, kind)` of every input, so unused inputs show up unconnected
+    pub fn new(scenes: &[SceneState], inputs: &[(String, String)]) -> Self {
+        let mut graph = Self::default();
+        for scene in scenes {
+            graph.add_node(&scene.name, SourceNode::Scene);
+        }
+        for scene in scenes {
+            graph.add_items(&scene.name, &scene.items);
+        }
+        for (name, kind) in inputs {
+            graph.add_node(name, SourceNode::Input { kind: kind.clone() });
+        }
+        graph
+    }
+
+    fn add_node(&mut self, name: &str, node: SourceNode) {
+        if !self.nodes.iter().any(|(existing, _)| existing == name) {
+            self.nodes.push((name.to_string(), node));
+        }
+    }
+
+    fn add_items(&mut self, container: &str, items: &[ItemState]) {
+        for item in items {
+            let node = match item.kind.as_deref() {
+                Some("group") => SourceNode::Group,
+                Some("scene") => SourceNode::Scene,
+                kind => SourceNode::Input {
+                    kind: kind.unwrap_or_default().to_string(),
+                },
+            };
+            self.add_node(&item.source, node);
+
+            let edge = (container.to_string(), item.source.clone());
+            if !self.edges.contains(&edge) {
+                self.edges.push(edge);
+            }
+            self.add_items(&item.source, &item.items);
+        }
+    }
+
+    /// Inputs that no scene or group shows, except the special inputs
+    pub fn orphans(&self, special_inputs: &[String]) -> Vec<(&str, &str)> {
+        self.nodes
+            .iter()
+            .filter_map(|(name, node)| match node {
+                SourceNode::Input { kind }
+                    if !special_inputs.contains(name)
+                        && !self.edges.iter().any(|(_, shown)| shown == name) =>
+                {
+                    Some((name.as_str(), kind.as_str()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Graphviz DOT: scenes are bold boxes, groups dashed boxes and inputs
+    /// ellipses labelled with their kind
+    pub fn to_dot(&self) -> String {
+        let escape = |text: &str| text.replace('\\', "\\\\").replace('"', "\\\"");
+        let quote = |text: &str| format!("\"{}\"", escape(text));
+        let mut dot = String::from("digraph obs {\n    rankdir=LR;\n");
+        for (name, node) in &self.nodes {
+            let attributes = match node {
+                SourceNode::Scene => "shape=box, style=bold".to_string(),
+                SourceNode::Group => "shape=box, style=dashed".to_string(),
+                SourceNode::Input { kind } => {
+                    format!(
+                        "shape=ellipse, label=\"{}\\n({})\"",
+                        escape(name),
+                        escape(kind)
+                    )
+                }
+            };
+            let _ = writeln!(dot, "    {} [{}];", quote(name), attributes);
+        }
+        for (container, source) in &self.edges {
+            let _ = writeln!(dot, "    {} -> {};", quote(container), quote(source));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Mermaid flowchart: scenes are boxes, groups subroutine boxes and
+    /// inputs rounded boxes labelled with their kind
+    pub fn to_mermaid(&self) -> String {
+        let label = |text: &str| text.replace('"', "#quot;");
+        let id = |name: &str| {
+            self.nodes
+                .iter()
+                .position(|(existing, _)| existing == name)
+                .map(|index| format!("n{}", index))
+                .unwrap_or_default()
+        };
+
+        let mut mermaid = String::from("graph LR\n");
+        for (index, (name, node)) in self.nodes.iter().enumerate() {
+            let shape = match node {
+                SourceNode::Scene => format!("[\"{}\"]", label(name)),
+                SourceNode::Group => format!("[[\"{}\"]]", label(name)),
+                SourceNode::Input { kind } => {
+                    format!("(\"{}<br/>({})\")", label(name), label(kind))
+                }
+            };
+            let _ = writeln!(mermaid, "    n{}{}", index, shape);
+        }
+        for (container, source) in &self.edges {
+            let _ = writeln!(mermaid, "    {} --> {}", id(container), id(source));
+        }
+        mermaid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SourceGraph, SourceNode};
+    use crate::state::SceneState;
+    use serde_json::json;
+
+    fn graph() -> SourceGraph {
+        let scenes: Vec<SceneState> = serde_json::from_value(json!([
+            {"name": "Live", "items": [
+                {"source": "Cams", "kind": "group", "items": [
+                    {"source": "Camera", "kind": "v4l2_input"}
+                ]},
+                {"source": "Camera", "kind": "v4l2_input"},
+                {"source": "Lower \"Third\"", "kind": "scene"}
+            ]},
+            {"name": "Lower \"Third\"", "items": [
+                {"source": "Title", "kind": "text_ft2_source_v2"}
+            ]}
+        ]))
+        .unwrap();
+        let inputs = [
+            ("Camera", "v4l2_input"),
+            ("Title", "text_ft2_source_v2"),
+            ("Old Clip", "ffmpeg_source"),
+            ("Desktop Audio", "pulse_output_capture"),
+        ]
+        .map(|(name, kind)| (name.to_string(), kind.to_string()));
+        SourceGraph::new(&scenes, &inputs)
+    }
+
+    #[test]
+    fn test_source_graph() {
+        let graph = graph();
+        assert_eq!(graph.nodes.len(), 7);
+        assert_eq!(graph.nodes[2], ("Cams".to_string(), SourceNode::Group));
+        assert_eq!(
+            graph.edges,
+            [
+                ("Live", "Cams"),
+                ("Cams", "Camera"),
+                ("Live", "Camera"),
+                ("Live", "Lower \"Third\""),
+                ("Lower \"Third\"", "Title"),
+            ]
+            .map(|(a, b)| (a.to_string(), b.to_string()))
+        );
+        assert_eq!(
+            graph.orphans(&["Desktop Audio".to_string()]),
+            [("Old Clip", "ffmpeg_source")]
+        );
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph obs {\n"));
+        assert!(dot.contains("    \"Live\" [shape=box, style=bold];\n"));
+        assert!(dot.contains("    \"Live\" -> \"Lower \\\"Third\\\"\";\n"));
+        assert!(dot.contains("label=\"Camera\\n(v4l2_input)\""));
+
+        let mermaid = graph.to_mermaid();
+        assert!(mermaid.starts_with("graph LR\n    n0[\"Live\"]\n"));
+        assert!(mermaid.contains("    n2[[\"Cams\"]]\n"));
+        assert!(mermaid.contains("    n1[\"Lower #quot;Third#quot;\"]\n"));
+        assert!(mermaid.contains("    n0 --> n2\n    n2 --> n3\n"));
+    }
+}
@@ -1,14 +1,17 @@
-use crate::cli::Commands;
+use crate::cli::{Commands, ObsWebsocket, Scene, SceneItem, Snapshot, TransitionOverride};
 use crate::connection::check_connection_health;
 use crate::error::Result;
 use crate::handlers::{
-    audio::AudioHandler, config::ProfileHandler, config::RecordDirectoryHandler,
-    config::StreamServiceHandler, config::VideoSettingsHandler, filters::FilterHandler,
-    general::HotkeyHandler, general::HotkeyLister, general::InfoHandler, inputs::InputCmdHandler,
-    media::MediaInputHandler, recording::RecordingHandler, replay_buffer::ReplayBufferHandler,
-    scene_collections::SceneCollectionHandler, scene_items::SceneItemHandler, scenes::SceneHandler,
-    sources::SourceHandler, streaming::StreamingHandler, ui::FullscreenProjectorHandler,
-    ui::SourceProjectorHandler, virtual_camera::VirtualCameraHandler, CommandHandler,
+    apply::ApplyHandler, audio::AudioHandler, config::ProfileHandler,
+    config::RecordDirectoryHandler, config::StreamServiceHandler, config::VideoSettingsHandler,
+    diff::DiffHandler, filters::FilterHandler, general::HotkeyHandler, general::HotkeyLister,
+    general::InfoHandler, graph::GraphHandler, graph::OrphansHandler, inputs::InputCmdHandler,
+    media::MediaInputHandler, recording::RecordingHandler, rename::RenameHandler,
+    replay_buffer::ReplayBufferHandler, scene_collections::SceneCollectionHandler,
+    scene_items::SceneItemHandler, scenes::ClearTransitionOverrideHandler, scenes::SceneHandler,
+    snapshot::SnapshotHandler, sources::SourceHandler, streaming::StreamingHandler,
+    transitions::TransitionHandler, ui::FullscreenProjectorHandler, ui::SourceProjectorHandler,
+    virtual_camera::VirtualCameraHandler, CommandHandler,
 };
 use obws::Client;
 
@@ -17,7 +20,13 @@ use obws::Client;
 /// This function is the main command dispatcher that takes a client connection
 /// and a command enum, then executes the corresponding handler.
 /// It includes connection health checking and comprehensive error handling.
-pub async fn handle_commands(client: &Client, commands: &Commands) -> Result<()> {
+/// `server` is where `client` is connected, for the few requests obws
+/// cannot send.
+pub async fn handle_commands(
+    client: &Client,
+    server: &ObsWebsocket,
+    commands: &Commands,
+) -> Result<()> {
     // Check connection health before executing commands
     if let Err(e) = check_connection_health(client).await {
         eprintln!("Warning: Connection health check failed: {}", e);
@@ -34,9 +43,18 @@ pub async fn handle_commands(client: &Client, commands: &Commands) -> Result<()>
             action: action.clone(),
         }),
 
+        Commands::Scene(Scene::TransitionOverride(TransitionOverride::Clear { scene_name })) => {
+            Box::new(ClearTransitionOverrideHandler {
+                scene_name: scene_name.clone(),
+                server: server.clone(),
+            })
+        }
         Commands::Scene(action) => Box::new(SceneHandler {
             action: action.clone(),
         }),
+        Commands::Transition(action) => Box::new(TransitionHandler {
+            action: action.clone(),
+        }),
         Commands::SceneCollection(action) => Box::new(SceneCollectionHandler {
             action: action.clone(),
         }),
@@ -64,22 +82,27 @@ pub async fn handle_commands(client: &Client, commands: &Commands) -> Result<()>
         Commands::Replay(action) => Box::new(ReplayBufferHandler {
             action: action.clone(),
         }),
-        Commands::Audio { command, device } => Box::new(AudioHandler {
-            command: command.clone(),
-            device: device.clone(),
+        Commands::Audio(action) => Box::new(AudioHandler {
+            action: action.clone(),
         }),
-        Commands::Filter {
-            command,
-            source,
-            filter,
-        } => Box::new(FilterHandler {
-            command: command.clone(),
-            source: source.clone(),
-            filter: filter.clone(),
+        Commands::Filter(action) => Box::new(FilterHandler {
+            action: action.clone(),
         }),
         Commands::SceneItem(action) => Box::new(SceneItemHandler {
             action: action.clone(),
         }),
+        Commands::Snapshot(action) => Box::new(SnapshotHandler {
+            action: action.clone(),
+        }),
+        Commands::Apply { file, yes, prune } => Box::new(ApplyHandler {
+            file: file.clone(),
+            yes: *yes,
+            prune: *prune,
+        }),
+        Commands::Diff(args) => Box::new(DiffHandler { args: args.clone() }),
+        Commands::Graph { format } => Box::new(GraphHandler { format: *format }),
+        Commands::Orphans { remove } => Box::new(OrphansHandler { remove: *remove }),
+        Commands::Rename(args) => Box::new(RenameHandler { args: args.clone() }),
         Commands::ListHotkeys => Box::new(HotkeyLister),
         Commands::TriggerHotkey { name } => Box::new(HotkeyHandler { name: name.clone() }),
         Commands::FullscreenProjector { monitor_index } => Box::new(FullscreenProjectorHandler {
@@ -111,8 +134,28 @@ pub async fn handle_commands(client: &Client, commands: &Commands) -> Result<()>
             // This should never reach here as completion is handled in main()
             panic!("Completion command should be handled in main()");
         }
+        Commands::Lint { .. } => {
+            // Linting needs no connection and is handled in main()
+            panic!("Lint command should be handled in main()");
+        }
     };
 
-    println!("Executing: {}", handler.description());
+    if writes_document_to_stdout(commands) {
+        // Keep stdout a valid document so it can be piped or redirected
+        eprintln!("Executing: {}", handler.description());
+    } else {
+        println!("Executing: {}", handler.description());
+    }
     handler.execute(client).await
 }
+
+/// Whether the command writes a document (JSON, DOT or Mermaid) to stdout
+pub fn writes_document_to_stdout(commands: &Commands) -> bool {
+    match commands {
+        Commands::Graph { .. } => true,
+        Commands::Snapshot(Snapshot::Export { output }) => output.is_none(),
+        Commands::SceneItem(SceneItem::List { json, .. }) => *json,
+        Commands::Diff(args) => args.json,
+        _ => false,
+    }
+}
@@ -0,0 +1,436 @@
+use crate::error::{ObsCmdError, Result};
+use crate::handlers::CommandHandler;
+use crate::plan::{count_steps, plan, Change, ItemRef, Step};
+use crate::state::{special_inputs, AudioState, ItemState, ObsState};
+use obws::common::{BlendMode, MonitorType};
+use obws::requests::config::SetVideoSettings;
+use obws::requests::filters::{
+    Create as CreateFilter, SetEnabled as SetFilterEnabled, SetIndex as SetFilterIndex,
+    SetSettings as SetFilterSettings,
+};
+use obws::requests::inputs::{Create as CreateInput, InputId, SetSettings, Volume};
+use obws::requests::scene_items::{
+    CreateSceneItem, SetBlendMode, SetEnabled, SetIndex, SetLocked, SetTransform,
+};
+use obws::requests::scenes::SceneId;
+use obws::requests::sources::SourceId;
+use obws::Client;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Handler for the `apply` command
+pub struct ApplyHandler {
+    pub file: PathBuf,
+    pub yes: bool,
+    pub prune: bool,
+}
+
+#[async_trait::async_trait]
+impl CommandHandler for ApplyHandler {
+    async fn execute(&self, client: &Client) -> Result<()> {
+        let desired = ObsState::load(&self.file)?;
+        let live = ObsState::capture(client).await?;
+        let special_inputs = special_inputs(client).await?;
+
+        let steps = plan(&desired, &live, self.prune, &special_inputs)?;
+        if steps.is_empty() {
+            println!("No changes. OBS matches {}", self.file.display());
+            return Ok(());
+        }
+
+        for step in &steps {
+            println!("{}", step);
+        }
+        let (create, update, remove) = count_steps(&steps);
+        println!(
+            "\nPlan: {} to create, {} to update, {} to remove.",
+            create, update, remove
+        );
+        if !self.yes {
+            println!("Run again with --yes to apply these changes.");
+            return Ok(());
+        }
+
+        // Items created by the plan, by container and declared position
+        let mut created = HashMap::new();
+        for step in &steps {
+            apply_step(client, step, &mut created).await?;
+            println!("Applied {} {}", step.symbol(), step.summary());
+        }
+        println!(
+            "Apply complete: {} created, {} updated, {} removed",
+            create, update, remove
+        );
+        Ok(())
+    }
+
+    fn description(&self) -> &'static str {
+        if self.yes {
+            "Apply OBS state file"
+        } else {
+            "Plan OBS state changes"
+        }
+    }
+}
+
+async fn apply_step(
+    client: &Client,
+    step: &Step,
+    created: &mut HashMap<(String, usize), i64>,
+) -> Result<()> {
+    match step {
+        Step::SetVideo { video, .. } => client
+            .config()
+            .set_video_settings(SetVideoSettings {
+                fps_numerator: Some(video.fps_numerator),
+                fps_denominator: Some(video.fps_denominator),
+                base_width: Some(video.base_width),
+                base_height: Some(video.base_height),
+                output_width: Some(video.output_width),
+                output_height: Some(video.output_height),
+            })
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?,
+        Step::SetTransition { transition, .. } => {
+            let transitions = client.transitions();
+            transitions
+                .set_current(&transition.name)
+                .await
+                .map_err(|e| ObsCmdError::ConnectionError(e))?;
+            if let Some(duration) = transition.duration_ms {
+                transitions
+                    .set_current_duration(time::Duration::milliseconds(duration as i64))
+                    .await
+                    .map_err(|e| ObsCmdError::ConnectionError(e))?;
+            }
+            if let Some(settings) = &transition.settings {
+                transitions
+                    .set_current_settings(settings, Some(true))
+                    .await
+                    .map_err(|e| ObsCmdError::ConnectionError(e))?;
+            }
+        }
+        Step::CreateScene { name } => {
+            client
+                .scenes()
+                .create(name)
+                .await
+                .map_err(|e| ObsCmdError::ConnectionError(e))?;
+        }
+        Step::CreateInput {
+            input,
+            container,
+            slot,
+            item,
+        } => {
+            let new = client
+                .inputs()
+                .create(CreateInput {
+                    scene: SceneId::Name(container),
+                    input: &input.name,
+                    kind: &input.kind,
+                    settings: Some(&input.settings),
+                    // Stay hidden until the item is set up
+                    enabled: Some(false),
+                })
+                .await
+                .map_err(|e| ObsCmdError::ConnectionError(e))?;
+            if let Some(audio) = &input.audio {
+                set_audio(client, &input.name, audio).await?;
+            }
+            set_item_state(client, container, new.scene_item_id, item, None).await?;
+            created.insert((container.clone(), *slot), new.scene_item_id);
+        }
+        Step::UpdateInput { input, changes, .. } => {
+            if changed(Some(changes), "settings") {
+                client
+                    .inputs()
+                    .set_settings(SetSettings {
+                        input: InputId::Name(&input.name),
+                        settings: &input.settings,
+                        overlay: Some(true),
+                    })
+                    .await
+                    .map_err(|e| ObsCmdError::ConnectionError(e))?;
+            }
+            if let Some(audio) = input
+                .audio
+                .as_ref()
+                .filter(|_| changed(Some(changes), "audio"))
+            {
+                set_audio(client, &input.name, audio).await?;
+            }
+        }
+        Step::CreateItem {
+            container,
+            slot,
+            item,
+        } => {
+            let id = client
+                .scene_items()
+                .create(CreateSceneItem {
+                    scene: SceneId::Name(container),
+                    source: SourceId::Name(&item.source),
+                    enabled: Some(false),
+                })
+                .await
+                .map_err(|e| ObsCmdError::ConnectionError(e))?;
+            set_item_state(client, container, id, item, None).await?;
+            created.insert((container.clone(), *slot), id);
+        }
+        Step::UpdateItem {
+            container,
+            id,
+            item,
+            changes,
+        } => set_item_state(client, container, *id, item, Some(changes)).await?,
+        Step::RemoveItem { container, id, .. } => client
+            .scene_items()
+            .remove(SceneId::Name(container), *id)
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?,
+        Step::ReorderItems {
+            container,
+            group,
+            order,
+        } => reorder_items(client, container, *group, order, created).await?,
+        Step::CreateFilter { source, filter } => {
+            client
+                .filters()
+                .create(CreateFilter {
+                    source: SourceId::Name(source),
+                    filter: &filter.name,
+                    kind: &filter.kind,
+                    settings: Some(&filter.settings),
+                })
+                .await
+                .map_err(|e| ObsCmdError::ConnectionError(e))?;
+            if !filter.enabled {
+                set_filter_enabled(client, source, &filter.name, false).await?;
+            }
+        }
+        Step::UpdateFilter {
+            source,
+            filter,
+            changes,
+            ..
+        } => {
+            if changed(Some(changes), "settings") {
+                client
+                    .filters()
+                    .set_settings(SetFilterSettings {
+                        source: SourceId::Name(source),
+                        filter: &filter.name,
+                        settings: &filter.settings,
+                        overlay: Some(true),
+                    })
+                    .await
+                    .map_err(|e| ObsCmdError::ConnectionError(e))?;
+            }
+            if changed(Some(changes), "enabled") {
+                set_filter_enabled(client, source, &filter.name, filter.enabled).await?;
+            }
+        }
+        Step::RemoveFilter { source, name } => client
+            .filters()
+            .remove(SourceId::Name(source), name)
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?,
+        Step::ReorderFilters { source, order } => {
+            // Undeclared filters end up after the declared ones
+            for (index, filter) in order.iter().enumerate() {
+                client
+                    .filters()
+                    .set_index(SetFilterIndex {
+                        source: SourceId::Name(source),
+                        filter,
+                        index: index as u32,
+                    })
+                    .await
+                    .map_err(|e| ObsCmdError::ConnectionError(e))?;
+            }
+        }
+        Step::RemoveScene { name } => client
+            .scenes()
+            .remove(SceneId::Name(name))
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?,
+        Step::RemoveInput { name } => client
+            .inputs()
+            .remove(InputId::Name(name))
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?,
+    }
+    Ok(())
+}
+
+/// Whether a change list touches a field or its sub-fields. `None` stands
+/// for a new object, where everything has to be set
+fn changed(changes: Option<&[Change]>, field: &str) -> bool {
+    changes.is_none_or(|changes| {
+        changes
+            .iter()
+            .any(|change| change.field == field || change.field.starts_with(&format!("{}.", field)))
+    })
+}
+
+/// Sets the changed properties of a scene item, or all of them for a new
+/// item. Visibility comes last so new items appear fully set up
+async fn set_item_state(
+    client: &Client,
+    container: &str,
+    item_id: i64,
+    item: &ItemState,
+    changes: Option<&[Change]>,
+) -> Result<()> {
+    let scene = SceneId::Name(container);
+    let scene_items = client.scene_items();
+    if changed(changes, "transform") {
+        scene_items
+            .set_transform(SetTransform {
+                scene,
+                item_id,
+                transform: item.transform.request(),
+            })
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    }
+    if changed(changes, "blend_mode") {
+        scene_items
+            .set_blend_mode(SetBlendMode {
+                scene,
+                item_id,
+                mode: BlendMode::from(item.blend_mode),
+            })
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    }
+    if changed(changes, "locked") {
+        scene_items
+            .set_locked(SetLocked {
+                scene,
+                item_id,
+                locked: item.locked,
+            })
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    }
+    if changed(changes, "enabled") {
+        scene_items
+            .set_enabled(SetEnabled {
+                scene,
+                item_id,
+                enabled: item.enabled,
+            })
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    }
+    Ok(())
+}
+
+async fn set_audio(client: &Client, input: &str, audio: &AudioState) -> Result<()> {
+    let id = InputId::Name(input);
+    let inputs = client.inputs();
+    inputs
+        .set_volume(id, Volume::Mul(audio.volume_mul))
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    inputs
+        .set_muted(id, audio.muted)
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    inputs
+        .set_audio_balance(id, audio.balance)
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    inputs
+        .set_audio_sync_offset(id, time::Duration::milliseconds(audio.sync_offset_ms))
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    inputs
+        .set_audio_monitor_type(id, MonitorType::from(audio.monitor_type))
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+
+    let mut tracks = [Some(false); 6];
+    for track in &audio.tracks {
+        if let Some(enabled) = tracks.get_mut((*track as usize).wrapping_sub(1)) {
+            *enabled = Some(true);
+        }
+    }
+    inputs
+        .set_audio_tracks(id, tracks)
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))
+}
+
+async fn set_filter_enabled(
+    client: &Client,
+    source: &str,
+    filter: &str,
+    enabled: bool,
+) -> Result<()> {
+    client
+        .filters()
+        .set_enabled(SetFilterEnabled {
+            source: SourceId::Name(source),
+            filter,
+            enabled,
+        })
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))
+}
+
+/// Puts the declared items in order from the top. Undeclared items that
+/// were kept end up below them
+async fn reorder_items(
+    client: &Client,
+    container: &str,
+    group: bool,
+    order: &[ItemRef],
+    created: &HashMap<(String, usize), i64>,
+) -> Result<()> {
+    let mut declared = Vec::with_capacity(order.len());
+    for item in order {
+        declared.push(match item {
+            ItemRef::Existing(id) => *id,
+            ItemRef::Created(slot) => {
+                *created
+                    .get(&(container.to_string(), *slot))
+                    .ok_or_else(|| ObsCmdError::InvalidStatePlan {
+                        message: format!("item {} of '{}' was not created", slot + 1, container),
+                    })?
+            }
+        });
+    }
+
+    let mut items = if group {
+        client
+            .scene_items()
+            .list_group(SceneId::Name(container))
+            .await
+    } else {
+        client.scene_items().list(SceneId::Name(container)).await
+    }
+    .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    items.sort_by_key(|item| std::cmp::Reverse(item.index));
+
+    let extras: Vec<i64> = items
+        .iter()
+        .map(|item| item.id)
+        .filter(|id| !declared.contains(id))
+        .collect();
+    // Index 0 is the bottom of the list
+    for (index, item_id) in declared.iter().chain(&extras).rev().enumerate() {
+        client
+            .scene_items()
+            .set_index(SetIndex {
+                scene: SceneId::Name(container),
+                item_id: *item_id,
+                index: index as u32,
+            })
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    }
+    Ok(())
+}
@@ -1,83 +1,365 @@
+use crate::cli::{Audio, VolumeValue};
 use crate::error::{ObsCmdError, Result};
 use crate::handlers::CommandHandler;
+use crate::state::{capture_input_audio, AudioState, MonitorTypeState};
+use futures_util::StreamExt;
+use obws::events::{Event, EventStream};
+use obws::requests::inputs::Volume;
 use obws::Client;
+use std::fmt::Write;
+use std::time::Duration;
 
-/// Handler for audio mute commands
+/// Handler for audio commands
 pub struct AudioHandler {
-    pub command: String,
-    pub device: String,
+    pub action: Audio,
 }
 
 #[async_trait::async_trait]
 impl CommandHandler for AudioHandler {
     async fn execute(&self, client: &Client) -> Result<()> {
-        match self.command.as_str() {
-            "mute" => {
-                println!("Muting audio device: {}", self.device);
+        match &self.action {
+            Audio::Mute { device } => {
+                println!("Muting audio device: {}", device);
                 client
                     .inputs()
-                    .set_muted(self.device.as_str().into(), true)
+                    .set_muted(device.as_str().into(), true)
                     .await
                     .map_err(|e| ObsCmdError::ConnectionError(e))?;
                 println!("Audio device muted successfully");
             }
-            "unmute" => {
-                println!("Unmuting audio device: {}", self.device);
+            Audio::Unmute { device } => {
+                println!("Unmuting audio device: {}", device);
                 client
                     .inputs()
-                    .set_muted(self.device.as_str().into(), false)
+                    .set_muted(device.as_str().into(), false)
                     .await
                     .map_err(|e| ObsCmdError::ConnectionError(e))?;
                 println!("Audio device unmuted successfully");
             }
-            "toggle" => {
+            Audio::Toggle { device } => {
                 let current_state = client
                     .inputs()
-                    .muted(self.device.as_str().into())
+                    .muted(device.as_str().into())
                     .await
                     .map_err(|e| ObsCmdError::ConnectionError(e))?;
                 let new_state = !current_state;
                 println!(
                     "Toggling audio device {}: {} -> {}",
-                    self.device,
+                    device,
                     if current_state { "muted" } else { "unmuted" },
                     if new_state { "muted" } else { "unmuted" }
                 );
                 client
                     .inputs()
-                    .set_muted(self.device.as_str().into(), new_state)
+                    .set_muted(device.as_str().into(), new_state)
                     .await
                     .map_err(|e| ObsCmdError::ConnectionError(e))?;
                 println!("Audio device toggled successfully");
             }
-            "status" => {
+            Audio::Status { device } => {
                 let status = client
                     .inputs()
-                    .muted(self.device.as_str().into())
+                    .muted(device.as_str().into())
                     .await
                     .map_err(|e| ObsCmdError::ConnectionError(e))?;
                 println!(
                     "Audio device '{}' is: {}",
-                    self.device,
+                    device,
                     if status { "muted" } else { "unmuted" }
                 );
             }
-            _ => {
-                return Err(ObsCmdError::InvalidAudioCommand {
-                    command: self.command.clone(),
-                });
+            Audio::Volume { device, value } => {
+                let current = client
+                    .inputs()
+                    .volume(device.as_str().into())
+                    .await
+                    .map_err(|e| ObsCmdError::ConnectionError(e))?
+                    .mul;
+                match value {
+                    None => println!("Volume of '{}': {}", device, volume_summary(current)),
+                    Some(value) => {
+                        let new_volume = resolve_volume(*value, current);
+                        client
+                            .inputs()
+                            .set_volume(device.as_str().into(), Volume::Mul(new_volume))
+                            .await
+                            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+                        println!(
+                            "Volume of '{}': {} -> {}",
+                            device,
+                            volume_summary(current),
+                            volume_summary(new_volume)
+                        );
+                    }
+                }
             }
+            Audio::Mixer { watch: false } => print!("{}", mixer_table(&mixer(client).await?)),
+            Audio::Mixer { watch: true } => watch_mixer(client).await?,
         };
         Ok(())
     }
 
     fn description(&self) -> &'static str {
-        match self.command.as_str() {
-            "mute" => "Mute audio device",
-            "unmute" => "Unmute audio device",
-            "toggle" => "Toggle audio device mute state",
-            "status" => "Get audio device status",
-            _ => "Audio operation",
+        match &self.action {
+            Audio::Mute { .. } => "Mute audio device",
+            Audio::Unmute { .. } => "Unmute audio device",
+            Audio::Toggle { .. } => "Toggle audio device mute state",
+            Audio::Status { .. } => "Get audio device status",
+            Audio::Volume { value: None, .. } => "Get audio device volume",
+            Audio::Volume { .. } => "Set audio device volume",
+            Audio::Mixer { .. } => "Show audio mixer",
         }
     }
 }
+
+/// The inputs with audio and their mixer settings, in input list order
+async fn mixer(client: &Client) -> Result<Vec<(String, AudioState)>> {
+    let inputs = client
+        .inputs()
+        .list(None)
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    let mut rows = Vec::new();
+    for input in inputs {
+        if let Some(audio) = capture_input_audio(client, &input.id.name).await? {
+            rows.push((input.id.name, audio));
+        }
+    }
+    Ok(rows)
+}
+
+/// Redraws the mixer table after every change until Ctrl-C or until the
+/// connection closes
+async fn watch_mixer(client: &Client) -> Result<()> {
+    let mut events = client
+        .events()
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    loop {
+        // Clear the terminal and move the cursor home before redrawing
+        print!("\x1b[2J\x1b[H{}", mixer_table(&mixer(client).await?));
+        println!("\nWatching for changes, press Ctrl-C to stop");
+
+        tokio::select! {
+            changed = wait_for_mixer_change(&mut events) => {
+                if !changed {
+                    println!("Connection closed");
+                    return Ok(());
+                }
+            }
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+/// Waits for an event that changes the mixer, then lets the burst it
+/// starts (such as a fader being dragged) settle. False when the event
+/// stream ended.
+async fn wait_for_mixer_change(events: &mut EventStream) -> bool {
+    loop {
+        match events.next().await {
+            Some(event) if is_mixer_event(&event) => break,
+            Some(_) => {}
+            None => return false,
+        }
+    }
+    while let Ok(event) = tokio::time::timeout(Duration::from_millis(100), events.next()).await {
+        if event.is_none() {
+            return false;
+        }
+    }
+    true
+}
+
+fn is_mixer_event(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::InputCreated { .. }
+            | Event::InputRemoved { .. }
+            | Event::InputNameChanged { .. }
+            | Event::InputMuteStateChanged { .. }
+            | Event::InputVolumeChanged { .. }
+            | Event::InputAudioBalanceChanged { .. }
+            | Event::InputAudioSyncOffsetChanged { .. }
+            | Event::InputAudioTracksChanged { .. }
+            | Event::InputAudioMonitorTypeChanged { .. }
+    )
+}
+
+/// The lowest level OBS accepts in dB; anything quieter is silence
+const MIN_DB: f64 = -100.0;
+
+/// The highest multiplier OBS accepts (about +26 dB)
+const MAX_MUL: f64 = 20.0;
+
+fn mul_to_db(volume_mul: f64) -> f64 {
+    if volume_mul > 0.0 {
+        (20.0 * volume_mul.log10()).max(MIN_DB)
+    } else {
+        MIN_DB
+    }
+}
+
+fn db_to_mul(db: f64) -> f64 {
+    if db > MIN_DB {
+        10f64.powf(db / 20.0)
+    } else {
+        0.0
+    }
+}
+
+/// The multiplier a volume value sets, given the current multiplier and
+/// clamped to what OBS accepts. dB steps from silence start at -100 dB.
+fn resolve_volume(value: VolumeValue, current_mul: f32) -> f32 {
+    let current = f64::from(current_mul);
+    let mul = match value {
+        VolumeValue::Mul(mul) => mul,
+        VolumeValue::Db(db) => db_to_mul(db),
+        VolumeValue::Percent(percent) => percent / 100.0,
+        VolumeValue::DbStep(step) => db_to_mul(mul_to_db(current) + step),
+        VolumeValue::PercentStep(step) => current + step / 100.0,
+    };
+    mul.clamp(0.0, MAX_MUL) as f32
+}
+
+/// Volume in dB the way OBS shows it, with silence as -inf
+pub(crate) fn format_db(volume_mul: f32) -> String {
+    if volume_mul > 0.0 {
+        format!("{:.1} dB", 20.0 * volume_mul.log10())
+    } else {
+        "-inf dB".to_string()
+    }
+}
+
+/// Volume in every unit: "-6.0 dB, 50% (multiplier 0.501)"
+pub(crate) fn volume_summary(volume_mul: f32) -> String {
+    format!(
+        "{}, {:.0}% (multiplier {:.3})",
+        format_db(volume_mul),
+        volume_mul * 100.0,
+        volume_mul
+    )
+}
+
+/// Lays out the mixer as a table with one row per input and an x for
+/// each track (1 to 6) the input is mixed into
+fn mixer_table(rows: &[(String, AudioState)]) -> String {
+    if rows.is_empty() {
+        return "No inputs with audio\n".to_string();
+    }
+    let width = rows
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .chain(["INPUT".len()])
+        .max()
+        .unwrap_or_default();
+
+    let mut table = format!(
+        "{:<width$}  {:>9}  {:>6}  {:<5}  {:>7}  {:>8}  {:<18}  TRACKS\n",
+        "INPUT", "VOLUME", "MUL", "MUTED", "BALANCE", "SYNC", "MONITOR"
+    );
+    let _ = writeln!(
+        table,
+        "{:<width$}  {:>9}  {:>6}  {:<5}  {:>7}  {:>8}  {:<18}  1 2 3 4 5 6",
+        "", "", "", "", "", "", ""
+    );
+    for (name, audio) in rows {
+        let monitor = match audio.monitor_type {
+            MonitorTypeState::None => "off",
+            MonitorTypeState::MonitorOnly => "monitor only",
+            MonitorTypeState::MonitorAndOutput => "monitor and output",
+        };
+        let tracks: Vec<&str> = (1..=6)
+            .map(|track| {
+                if audio.tracks.contains(&track) {
+                    "x"
+                } else {
+                    "-"
+                }
+            })
+            .collect();
+        let _ = writeln!(
+            table,
+            "{:<width$}  {:>9}  {:>6.3}  {:<5}  {:>7.2}  {:>8}  {:<18}  {}",
+            name,
+            format_db(audio.volume_mul),
+            audio.volume_mul,
+            if audio.muted { "yes" } else { "no" },
+            audio.balance,
+            format!("{} ms", audio.sync_offset_ms),
+            monitor,
+            tracks.join(" ")
+        );
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_db, mixer_table, resolve_volume, volume_summary};
+    use crate::cli::VolumeValue;
+    use crate::state::{AudioState, MonitorTypeState};
+
+    #[test]
+    fn test_resolve_volume() {
+        let close = |value: VolumeValue, current: f32, expected: f32| {
+            let resolved = resolve_volume(value, current);
+            assert!(
+                (resolved - expected).abs() < 1e-3,
+                "{:?} from {}: {} != {}",
+                value,
+                current,
+                resolved,
+                expected
+            );
+        };
+        close(VolumeValue::Mul(0.5), 1.0, 0.5);
+        close(VolumeValue::Db(-6.0), 1.0, 0.501);
+        close(VolumeValue::Db(-120.0), 1.0, 0.0);
+        close(VolumeValue::Percent(75.0), 0.1, 0.75);
+        close(VolumeValue::DbStep(3.0), 0.501, 0.708);
+        close(VolumeValue::DbStep(-6.0), 1.0, 0.501);
+        close(VolumeValue::PercentStep(-10.0), 0.75, 0.65);
+        // Clamped to what OBS accepts
+        close(VolumeValue::PercentStep(-10.0), 0.05, 0.0);
+        close(VolumeValue::Db(40.0), 1.0, 20.0);
+        close(VolumeValue::Mul(-1.0), 1.0, 0.0);
+        // Steps up from silence start at -100 dB
+        close(VolumeValue::DbStep(94.0), 0.0, 0.501);
+
+        assert_eq!(volume_summary(0.5), "-6.0 dB, 50% (multiplier 0.500)");
+        assert_eq!(volume_summary(0.0), "-inf dB, 0% (multiplier 0.000)");
+    }
+
+    #[test]
+    fn test_mixer_table() {
+        let rows = [
+            ("Desktop Audio".to_string(), AudioState::default()),
+            (
+                "Mic".to_string(),
+                AudioState {
+                    volume_mul: 0.5,
+                    muted: true,
+                    balance: 0.25,
+                    sync_offset_ms: -120,
+                    monitor_type: MonitorTypeState::MonitorAndOutput,
+                    tracks: vec![1, 3],
+                },
+            ),
+        ];
+        let table = mixer_table(&rows);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("INPUT             VOLUME"));
+        assert!(lines[1].ends_with("1 2 3 4 5 6"));
+        assert_eq!(
+            lines[2],
+            "Desktop Audio     0.0 dB   1.000  no        0.50      0 ms  off                 x x x x x x"
+        );
+        assert_eq!(
+            lines[3],
+            "Mic              -6.0 dB   0.500  yes       0.25   -120 ms  monitor and output  x - x - - -"
+        );
+        assert_eq!(mixer_table(&[]), "No inputs with audio\n");
+        assert_eq!(format_db(0.0), "-inf dB");
+    }
+}
@@ -0,0 +1,140 @@
+use crate::cli::{DiffArgs, ObsWebsocket};
+use crate::connection::{connect_with_retry, ConnectionConfig};
+use crate::diff::{diff_states, DiffNode};
+use crate::error::{ObsCmdError, Result};
+use crate::handlers::CommandHandler;
+use crate::state::ObsState;
+use crate::user_config::UserConfig;
+use obws::Client;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Handler for the `diff` command
+pub struct DiffHandler {
+    pub args: DiffArgs,
+}
+
+#[async_trait::async_trait]
+impl CommandHandler for DiffHandler {
+    async fn execute(&self, client: &Client) -> Result<()> {
+        run_diff(&self.args, Some(client)).await
+    }
+
+    fn description(&self) -> &'static str {
+        "Compare OBS states"
+    }
+}
+
+/// One of the two compared states
+enum Side {
+    File(PathBuf),
+    Target(String),
+    Connected,
+}
+
+impl Side {
+    fn label(&self) -> String {
+        match self {
+            Side::File(path) => path.display().to_string(),
+            Side::Target(target) => target.clone(),
+            Side::Connected => "connected OBS".to_string(),
+        }
+    }
+
+    async fn state(&self, client: Option<&Client>) -> Result<ObsState> {
+        match self {
+            Side::File(path) => ObsState::load(path),
+            Side::Target(target) => {
+                let ObsWebsocket {
+                    hostname,
+                    port,
+                    password,
+                } = resolve_target(target)?;
+                let client =
+                    connect_with_retry(hostname, port, password, ConnectionConfig::default())
+                        .await?;
+                ObsState::capture(&client).await
+            }
+            Side::Connected => match client {
+                Some(client) => ObsState::capture(client).await,
+                None => Err(ObsCmdError::InvalidDiffSides),
+            },
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct DiffReport<'a> {
+    from: String,
+    to: String,
+    count: usize,
+    differences: &'a [DiffNode],
+}
+
+/// Compares the states picked by `args`. `client` is the default
+/// connection, which is only needed when fewer than two other states were
+/// given
+pub async fn run_diff(args: &DiffArgs, client: Option<&Client>) -> Result<()> {
+    let mut sides: Vec<Side> = args
+        .file
+        .iter()
+        .map(|path| Side::File(path.clone()))
+        .chain(
+            args.targets
+                .iter()
+                .map(|target| Side::Target(target.clone())),
+        )
+        .collect();
+    if sides.is_empty() || sides.len() > 2 {
+        return Err(ObsCmdError::InvalidDiffSides);
+    }
+    if sides.len() == 1 {
+        sides.push(Side::Connected);
+    }
+
+    let from = sides[0].state(client).await?;
+    let to = sides[1].state(client).await?;
+    let nodes = diff_states(&from, &to);
+    let count = nodes.iter().map(DiffNode::count).sum();
+
+    let report = DiffReport {
+        from: sides[0].label(),
+        to: sides[1].label(),
+        count,
+        differences: &nodes,
+    };
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else if nodes.is_empty() {
+        println!("No differences between {} and {}", report.from, report.to);
+    } else {
+        println!("--- {}\n+++ {}", report.from, report.to);
+        for node in &nodes {
+            print!("{}", node);
+        }
+        println!("\n{} difference(s)", count);
+    }
+
+    if args.exit_code && count > 0 {
+        return Err(ObsCmdError::StatesDiffer { count });
+    }
+    Ok(())
+}
+
+/// Looks up a target in the config file, unless it is a URL already
+fn resolve_target(target: &str) -> Result<ObsWebsocket> {
+    let url = if target.starts_with("obsws://") {
+        target.to_string()
+    } else {
+        UserConfig::load()?
+            .targets
+            .get(target)
+            .cloned()
+            .ok_or_else(|| ObsCmdError::TargetNotFound {
+                name: target.to_string(),
+            })?
+    };
+    url.parse().map_err(|e: &str| {
+        ObsCmdError::WebSocketUrlParseError(format!("{} (target '{}')", e, target))
+    })
+}
@@ -1,63 +1,459 @@
+use crate::animation::{lerp, Timeline};
+use crate::cli::{AbortSnap, Filter, FilterAnimation, FilterPreset, FilterTarget};
 use crate::error::{ObsCmdError, Result};
-use crate::handlers::CommandHandler;
-use obws::requests::filters::SetEnabled as SetEnabledFilter;
+use crate::handlers::{glob_match, CommandHandler};
+use crate::user_config::{FilterPresetEntry, UserConfig};
+use obws::requests::filters::{
+    Create as CreateFilter, SetEnabled as SetEnabledFilter, SetIndex as SetFilterIndex, SetSettings,
+};
 use obws::Client;
+use serde_json::json;
 
 /// Handler for filter enable/disable commands
 pub struct FilterHandler {
-    pub command: String,
-    pub source: String,
-    pub filter: String,
+    pub action: Filter,
+}
+
+/// The state change requested for the selected filters
+#[derive(Clone, Copy)]
+enum FilterChange {
+    Enable,
+    Disable,
+    Toggle,
+}
+
+impl FilterChange {
+    fn apply(self, current: bool) -> bool {
+        match self {
+            FilterChange::Enable => true,
+            FilterChange::Disable => false,
+            FilterChange::Toggle => !current,
+        }
+    }
 }
 
 #[async_trait::async_trait]
 impl CommandHandler for FilterHandler {
     async fn execute(&self, client: &Client) -> Result<()> {
-        let enabled: bool = match self.command.as_str() {
-            "enable" => true,
-            "disable" => false,
-            "toggle" => {
-                let current_state = client
+        let (target, change) = match &self.action {
+            Filter::Enable(target) => (target, FilterChange::Enable),
+            Filter::Disable(target) => (target, FilterChange::Disable),
+            Filter::Toggle(target) => (target, FilterChange::Toggle),
+            Filter::Animate(animation) => return animate_filter_setting(client, animation).await,
+            Filter::Preset(FilterPreset::Apply {
+                source,
+                preset,
+                replace,
+            }) => return apply_filter_preset(client, source, preset, *replace).await,
+            Filter::Preset(FilterPreset::Save { source, name }) => {
+                return save_filter_preset(client, source, name).await
+            }
+        };
+
+        match (&target.source, &target.filter, &target.filter_glob) {
+            (Some(source), Some(filter), None) => {
+                set_single_filter(client, source, filter, change, target.dry_run).await
+            }
+            _ => set_matching_filters(client, target, change).await,
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match &self.action {
+            Filter::Enable(_) => "Enable filter",
+            Filter::Disable(_) => "Disable filter",
+            Filter::Toggle(_) => "Toggle filter",
+            Filter::Animate(_) => "Animate filter setting",
+            Filter::Preset(FilterPreset::Apply { .. }) => "Apply filter preset",
+            Filter::Preset(FilterPreset::Save { .. }) => "Save filter preset",
+        }
+    }
+}
+
+/// Applies a change to one filter addressed by exact source and filter name
+async fn set_single_filter(
+    client: &Client,
+    source: &str,
+    filter: &str,
+    change: FilterChange,
+    dry_run: bool,
+) -> Result<()> {
+    let current_state = client
+        .filters()
+        .get(source.into(), filter)
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?
+        .enabled;
+    let enabled = change.apply(current_state);
+
+    if dry_run {
+        println!(
+            "Filter '{}' on source '{}' would be {}",
+            filter,
+            source,
+            if enabled { "enabled" } else { "disabled" }
+        );
+        return Ok(());
+    }
+
+    client
+        .filters()
+        .set_enabled(SetEnabledFilter {
+            source: source.into(),
+            filter,
+            enabled,
+        })
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+
+    println!(
+        "Filter '{}' on source '{}': {}",
+        filter,
+        source,
+        if enabled { "enabled" } else { "disabled" }
+    );
+    println!("Filter operation completed successfully");
+    Ok(())
+}
+
+/// Applies a change to every filter matching the target's glob patterns,
+/// searching all inputs and scenes, and prints a per-source report
+async fn set_matching_filters(
+    client: &Client,
+    target: &FilterTarget,
+    change: FilterChange,
+) -> Result<()> {
+    let source_pattern = target.source_glob.as_deref().unwrap_or("*");
+    let filter_pattern = target.filter_glob.as_deref().unwrap_or("*");
+
+    let mut sources: Vec<String> = client
+        .inputs()
+        .list(None)
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?
+        .into_iter()
+        .map(|input| input.id.name)
+        .collect();
+    sources.extend(
+        client
+            .scenes()
+            .list()
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?
+            .scenes
+            .into_iter()
+            .map(|scene| scene.id.name),
+    );
+
+    let mut matched_sources = 0;
+    let mut matched_filters = 0;
+
+    for source in sources
+        .iter()
+        .filter(|source| glob_match(source_pattern, source))
+    {
+        let filters = client
+            .filters()
+            .list(source.as_str().into())
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+        let matching: Vec<_> = filters
+            .into_iter()
+            .filter(|filter| glob_match(filter_pattern, &filter.name))
+            .collect();
+        if matching.is_empty() {
+            continue;
+        }
+
+        matched_sources += 1;
+        println!("Source '{}':", source);
+        for filter in matching {
+            let enabled = change.apply(filter.enabled);
+            if !target.dry_run {
+                client
                     .filters()
-                    .get(self.source.as_str().into(), &self.filter)
+                    .set_enabled(SetEnabledFilter {
+                        source: source.as_str().into(),
+                        filter: &filter.name,
+                        enabled,
+                    })
                     .await
-                    .map_err(|e| ObsCmdError::ConnectionError(e))?
-                    .enabled;
-                !current_state
+                    .map_err(|e| ObsCmdError::ConnectionError(e))?;
             }
-            _ => {
-                return Err(ObsCmdError::InvalidFilterCommand {
-                    command: self.command.clone(),
-                });
+            matched_filters += 1;
+            println!(
+                "  - {}: {} -> {}",
+                filter.name,
+                if filter.enabled {
+                    "enabled"
+                } else {
+                    "disabled"
+                },
+                if enabled { "enabled" } else { "disabled" }
+            );
+        }
+    }
+
+    if target.dry_run {
+        println!(
+            "Dry run: {} filter(s) on {} source(s) matched, nothing changed",
+            matched_filters, matched_sources
+        );
+    } else {
+        println!(
+            "Updated {} filter(s) on {} source(s)",
+            matched_filters, matched_sources
+        );
+    }
+    Ok(())
+}
+
+/// Ramps a numeric filter setting towards its target, one
+/// SetSourceFilterSettings request per frame
+async fn animate_filter_setting(client: &Client, animation: &FilterAnimation) -> Result<()> {
+    let FilterAnimation {
+        source,
+        filter,
+        setting,
+        to,
+        from,
+        duration,
+        easing,
+        fps,
+        on_abort,
+    } = animation;
+
+    let current = client
+        .filters()
+        .get(source.as_str().into(), filter)
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    let current_value = current.settings.get(setting.as_str());
+    let start = match from {
+        Some(from) => *from,
+        None => current_value.and_then(|v| v.as_f64()).ok_or_else(|| {
+            ObsCmdError::NonNumericFilterSetting {
+                filter: filter.clone(),
+                setting: setting.clone(),
+            }
+        })?,
+    };
+    // Integer settings such as blur sizes must stay integers
+    let integral = current_value.is_some_and(|v| v.is_i64() || v.is_u64())
+        && start.fract() == 0.0
+        && to.fract() == 0.0;
+
+    println!(
+        "Animating '{}' of filter '{}' on source '{}': {} -> {} over {:.2}s",
+        setting,
+        filter,
+        source,
+        start,
+        to,
+        duration.as_secs_f64()
+    );
+
+    let set_value = |value: f64| async move {
+        let value = if integral {
+            json!(value.round() as i64)
+        } else {
+            json!(value)
+        };
+        client
+            .filters()
+            .set_settings(SetSettings {
+                source: source.as_str().into(),
+                filter,
+                settings: json!({ setting.as_str(): value }),
+                overlay: Some(true),
+            })
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))
+    };
+
+    let mut timeline = Timeline::new(*duration, *fps, *easing);
+    let animation = async {
+        set_value(start).await?;
+        while let Some(t) = timeline.next_frame().await {
+            set_value(lerp(start, *to, t)).await?;
+        }
+        Ok::<_, ObsCmdError>(())
+    };
+
+    tokio::select! {
+        result = animation => {
+            result?;
+            println!("Animation completed at {}", to);
+        }
+        _ = tokio::signal::ctrl_c() => {
+            let snap = match on_abort {
+                AbortSnap::Start => start,
+                AbortSnap::End => *to,
+            };
+            set_value(snap).await?;
+            println!("Animation interrupted, snapped to {}", snap);
+        }
+    }
+    Ok(())
+}
+
+/// Makes a source's filter chain match a preset from the config file.
+///
+/// Filters are matched by name: existing ones get the preset's kind, settings
+/// and enabled state, missing ones are created, and all of them are moved
+/// into the preset's order at the top of the chain.
+async fn apply_filter_preset(
+    client: &Client,
+    source: &str,
+    preset_name: &str,
+    replace: bool,
+) -> Result<()> {
+    let config = UserConfig::load()?;
+    let preset = config.filter_presets.get(preset_name).ok_or_else(|| {
+        ObsCmdError::FilterPresetNotFound {
+            name: preset_name.to_string(),
+        }
+    })?;
+
+    let existing = client
+        .filters()
+        .list(source.into())
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+
+    println!(
+        "Applying filter preset '{}' to source '{}':",
+        preset_name, source
+    );
+
+    if replace {
+        for filter in existing
+            .iter()
+            .filter(|f| !preset.iter().any(|entry| entry.name == f.name))
+        {
+            client
+                .filters()
+                .remove(source.into(), &filter.name)
+                .await
+                .map_err(|e| ObsCmdError::ConnectionError(e))?;
+            println!("  - {}: removed", filter.name);
+        }
+    }
+
+    for (index, entry) in preset.iter().enumerate() {
+        let current = existing.iter().find(|f| f.name == entry.name);
+        let action = match current {
+            Some(filter) if filter.kind == entry.kind => {
+                client
+                    .filters()
+                    .set_settings(SetSettings {
+                        source: source.into(),
+                        filter: &entry.name,
+                        settings: &entry.settings,
+                        overlay: Some(false),
+                    })
+                    .await
+                    .map_err(|e| ObsCmdError::ConnectionError(e))?;
+                "updated"
+            }
+            Some(_) => {
+                // A filter's kind cannot change, so replace it
+                client
+                    .filters()
+                    .remove(source.into(), &entry.name)
+                    .await
+                    .map_err(|e| ObsCmdError::ConnectionError(e))?;
+                create_preset_filter(client, source, entry).await?;
+                "recreated"
+            }
+            None => {
+                create_preset_filter(client, source, entry).await?;
+                "created"
             }
         };
 
         client
             .filters()
             .set_enabled(SetEnabledFilter {
-                source: self.source.as_str().into(),
-                filter: &self.filter,
-                enabled,
+                source: source.into(),
+                filter: &entry.name,
+                enabled: entry.enabled,
+            })
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+        client
+            .filters()
+            .set_index(SetFilterIndex {
+                source: source.into(),
+                filter: &entry.name,
+                index: index as u32,
             })
             .await
             .map_err(|e| ObsCmdError::ConnectionError(e))?;
 
-        println!(
-            "Filter '{}' on source '{}': {}",
-            self.filter,
-            self.source,
-            if enabled { "enabled" } else { "disabled" }
-        );
-        println!("Filter operation completed successfully");
-        Ok(())
+        println!("  - {} ({}): {}", entry.name, entry.kind, action);
     }
 
-    fn description(&self) -> &'static str {
-        match self.command.as_str() {
-            "enable" => "Enable filter",
-            "disable" => "Disable filter",
-            "toggle" => "Toggle filter",
-            _ => "Filter operation",
-        }
+    println!("Filter preset applied successfully");
+    Ok(())
+}
+
+async fn create_preset_filter(
+    client: &Client,
+    source: &str,
+    entry: &FilterPresetEntry,
+) -> Result<()> {
+    client
+        .filters()
+        .create(CreateFilter {
+            source: source.into(),
+            filter: &entry.name,
+            kind: &entry.kind,
+            settings: Some(&entry.settings),
+        })
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))
+}
+
+/// Captures a source's filter chain into the config file as a named preset
+async fn save_filter_preset(client: &Client, source: &str, name: &str) -> Result<()> {
+    let mut filters = client
+        .filters()
+        .list(source.into())
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    filters.sort_by_key(|f| f.index);
+
+    let entries: Vec<FilterPresetEntry> = filters
+        .into_iter()
+        .map(|f| FilterPresetEntry {
+            name: f.name,
+            kind: f.kind,
+            enabled: f.enabled,
+            settings: f.settings,
+        })
+        .collect();
+
+    let mut config = UserConfig::load()?;
+    for entry in &entries {
+        println!("  - {} ({})", entry.name, entry.kind);
     }
+    let count = entries.len();
+    if config
+        .filter_presets
+        .insert(name.to_string(), entries)
+        .is_some()
+    {
+        println!("Replacing existing filter preset '{}'", name);
+    }
+    let path = config.save()?;
+
+    println!(
+        "Saved {} filter(s) from source '{}' as preset '{}' in {}",
+        count,
+        source,
+        name,
+        path.display()
+    );
+    Ok(())
 }
@@ -0,0 +1,93 @@
+use crate::cli::GraphFormat;
+use crate::error::{ObsCmdError, Result};
+use crate::graph::SourceGraph;
+use crate::handlers::CommandHandler;
+use crate::state::{capture_scenes, special_inputs};
+use obws::requests::inputs::InputId;
+use obws::Client;
+
+/// Reads the scenes and inputs into a source graph
+async fn source_graph(client: &Client) -> Result<SourceGraph> {
+    let scenes = capture_scenes(client, false).await?;
+    let inputs: Vec<(String, String)> = client
+        .inputs()
+        .list(None)
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?
+        .into_iter()
+        .map(|input| (input.id.name, input.kind))
+        .collect();
+    Ok(SourceGraph::new(&scenes, &inputs))
+}
+
+/// Handler for the `graph` command
+pub struct GraphHandler {
+    pub format: GraphFormat,
+}
+
+impl GraphHandler {
+    /// The graph in the requested format, which is all the command prints
+    pub fn render(&self, graph: &SourceGraph) -> String {
+        match self.format {
+            GraphFormat::Dot => graph.to_dot(),
+            GraphFormat::Mermaid => graph.to_mermaid(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl CommandHandler for GraphHandler {
+    async fn execute(&self, client: &Client) -> Result<()> {
+        let graph = source_graph(client).await?;
+        print!("{}", self.render(&graph));
+        Ok(())
+    }
+
+    fn description(&self) -> &'static str {
+        "Print source usage graph"
+    }
+}
+
+/// Handler for the `orphans` command
+pub struct OrphansHandler {
+    pub remove: bool,
+}
+
+#[async_trait::async_trait]
+impl CommandHandler for OrphansHandler {
+    async fn execute(&self, client: &Client) -> Result<()> {
+        let graph = source_graph(client).await?;
+        let specials = special_inputs(client).await?;
+        let orphans = graph.orphans(&specials);
+        if orphans.is_empty() {
+            println!("No unused inputs");
+            return Ok(());
+        }
+
+        for (name, kind) in &orphans {
+            println!("{} ({})", name, kind);
+        }
+        if !self.remove {
+            println!("\n{} unused input(s)", orphans.len());
+            return Ok(());
+        }
+
+        for (name, _) in &orphans {
+            client
+                .inputs()
+                .remove(InputId::Name(name))
+                .await
+                .map_err(|e| ObsCmdError::ConnectionError(e))?;
+        }
+        println!("\nRemoved {} unused input(s)", orphans.len());
+        Ok(())
+    }
+
+    fn description(&self) -> &'static str {
+        if self.remove {
+            "Remove unused inputs"
+        } else {
+            "List unused inputs"
+        }
+    }
+}
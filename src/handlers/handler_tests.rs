@@ -1,16 +1,26 @@
 #[cfg(test)]
 mod tests {
     use crate::cli::{
-        Commands, Input, MediaInput, MuteAction, Recording, Replay, Scene, SceneCollection,
-        SceneItem, Streaming, VirtualCamera,
+        AbortSnap, Audio, Cli, Commands, Easing, Filter, FilterPreset, FilterTarget, GraphFormat,
+        Input, ItemAlignment, ItemBoundsType, ItemSelector, Layout, MediaInput, MuteAction,
+        ObsWebsocket, Recording, RenameArgs, Replay, RotationStop, Scene, SceneCollection,
+        SceneItem, Snapshot, Streaming, TransformValue, Transition, TransitionOverride,
+        VirtualCamera, VolumeValue,
     };
+    use crate::graph::SourceGraph;
+    use crate::handler::writes_document_to_stdout;
     use crate::handlers::{
-        audio::AudioHandler, filters::FilterHandler, general::HotkeyHandler, general::InfoHandler,
-        media::MediaInputHandler, recording::RecordingHandler,
-        scene_collections::SceneCollectionHandler, scenes::SceneHandler, sources::SourceHandler,
-        streaming::StreamingHandler, ui::FullscreenProjectorHandler, ui::SourceProjectorHandler,
-        validate_monitor_index, virtual_camera::VirtualCameraHandler, CommandHandler,
+        apply::ApplyHandler, audio::AudioHandler, diff::DiffHandler, filters::FilterHandler,
+        general::HotkeyHandler, general::InfoHandler, glob_match, graph::GraphHandler,
+        graph::OrphansHandler, media::MediaInputHandler, recording::RecordingHandler,
+        rename::RenameHandler, scene_collections::SceneCollectionHandler,
+        scene_items::SceneItemHandler, scenes::ClearTransitionOverrideHandler,
+        scenes::SceneHandler, snapshot::SnapshotHandler, sources::SourceHandler,
+        streaming::StreamingHandler, transitions::TransitionHandler,
+        ui::FullscreenProjectorHandler, ui::SourceProjectorHandler, validate_monitor_index,
+        virtual_camera::VirtualCameraHandler, CommandHandler,
     };
+    use clap::Parser;
     use std::path::PathBuf;
 
     // Mock client for testing (placeholder for future implementation)
@@ -103,17 +113,232 @@ mod tests {
     #[tokio::test]
     async fn test_scene_switch_command() {
         let command = Commands::Scene(Scene::Switch {
-            scene_name: "test_scene".to_string(),
+            scene_name: Some("test_scene".to_string()),
+            index: None,
+            transition: None,
+            duration: None,
         });
 
         match command {
-            Commands::Scene(Scene::Switch { scene_name }) => {
-                assert_eq!(scene_name, "test_scene");
+            Commands::Scene(Scene::Switch { scene_name, .. }) => {
+                assert_eq!(scene_name, Some("test_scene".to_string()));
             }
             _ => panic!("Expected Scene::Switch command"),
         }
     }
 
+    #[tokio::test]
+    async fn test_scene_switch_with_transition_command() {
+        let cli = Cli::try_parse_from([
+            "obs-cmd",
+            "scene",
+            "switch",
+            "Live",
+            "--transition",
+            "Stinger",
+            "--duration",
+            "800",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Commands::Scene(Scene::Switch {
+                scene_name,
+                transition,
+                duration,
+                ..
+            }) => {
+                assert_eq!(scene_name, Some("Live".to_string()));
+                assert_eq!(transition, Some("Stinger".to_string()));
+                assert_eq!(duration, Some(800));
+            }
+            _ => panic!("Expected Scene::Switch command"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scene_clone_command() {
+        let cli = Cli::try_parse_from(["obs-cmd", "scene", "clone", "Main", "Main Copy", "--deep"])
+            .unwrap();
+        match cli.command {
+            Commands::Scene(Scene::Clone {
+                scene_name,
+                new_name,
+                deep,
+            }) => {
+                assert_eq!(scene_name, "Main");
+                assert_eq!(new_name, "Main Copy");
+                assert!(deep);
+            }
+            _ => panic!("Expected Scene::Clone command"),
+        }
+
+        let handler = SceneHandler {
+            action: Scene::Clone {
+                scene_name: "Main".to_string(),
+                new_name: "Main Copy".to_string(),
+                deep: false,
+            },
+        };
+        assert_eq!(handler.description(), "Clone scene");
+    }
+
+    #[tokio::test]
+    async fn test_scene_navigation_commands() {
+        let cli = Cli::try_parse_from(["obs-cmd", "scene", "switch", "--index", "3"]).unwrap();
+        match cli.command {
+            Commands::Scene(Scene::Switch {
+                scene_name, index, ..
+            }) => {
+                assert_eq!(scene_name, None);
+                assert_eq!(index, Some(3));
+            }
+            _ => panic!("Expected Scene::Switch command"),
+        }
+        assert!(Cli::try_parse_from(["obs-cmd", "scene", "switch", "--index", "0"]).is_err());
+        assert!(Cli::try_parse_from(["obs-cmd", "scene", "switch"]).is_err());
+
+        let cli = Cli::try_parse_from([
+            "obs-cmd",
+            "scene",
+            "next",
+            "--wrap",
+            "--skip-pattern",
+            "[nested] *",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Scene(Scene::Next(cycle)) => {
+                assert!(cycle.wrap);
+                assert_eq!(cycle.skip_patterns, vec!["[nested] *".to_string()]);
+            }
+            _ => panic!("Expected Scene::Next command"),
+        }
+
+        let handler = SceneHandler {
+            action: Scene::Back,
+        };
+        assert_eq!(handler.description(), "Switch back to previous scene");
+    }
+
+    #[tokio::test]
+    async fn test_scene_rotate_command() {
+        let cli = Cli::try_parse_from([
+            "obs-cmd",
+            "scene",
+            "rotate",
+            "--scenes",
+            "BRB 1,BRB 2,BRB 3",
+            "--interval",
+            "20s",
+            "--shuffle",
+            "--until-event",
+            "stream-stopped",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Commands::Scene(Scene::Rotate(rotation)) => {
+                assert_eq!(rotation.scenes, vec!["BRB 1", "BRB 2", "BRB 3"]);
+                assert_eq!(rotation.interval, std::time::Duration::from_secs(20));
+                assert!(rotation.shuffle);
+                assert_eq!(rotation.transition, None);
+                assert_eq!(rotation.until_event, Some(RotationStop::StreamStopped));
+            }
+            _ => panic!("Expected Scene::Rotate command"),
+        }
+        assert!(Cli::try_parse_from(["obs-cmd", "scene", "rotate"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_studio_mode_workflow_commands() {
+        let cli = Cli::try_parse_from([
+            "obs-cmd",
+            "scene",
+            "take",
+            "--transition",
+            "Fade",
+            "--duration",
+            "500",
+            "--wait",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Scene(Scene::Take {
+                transition,
+                duration,
+                wait,
+            }) => {
+                assert_eq!(transition, Some("Fade".to_string()));
+                assert_eq!(duration, Some(500));
+                assert!(wait);
+            }
+            _ => panic!("Expected Scene::Take command"),
+        }
+
+        let cli = Cli::try_parse_from(["obs-cmd", "scene", "stage", "Live"]).unwrap();
+        match cli.command {
+            Commands::Scene(Scene::Stage { scene_name }) => assert_eq!(scene_name, "Live"),
+            _ => panic!("Expected Scene::Stage command"),
+        }
+
+        let handler = SceneHandler {
+            action: Scene::Swap,
+        };
+        assert_eq!(handler.description(), "Swap program and preview scenes");
+    }
+
+    #[tokio::test]
+    async fn test_scene_transition_override_commands() {
+        let cli = Cli::try_parse_from([
+            "obs-cmd",
+            "scene",
+            "transition-override",
+            "set",
+            "Live",
+            "--transition",
+            "Fade",
+            "--duration",
+            "300",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Scene(Scene::TransitionOverride(action)) => assert_eq!(
+                action,
+                TransitionOverride::Set {
+                    scene_name: "Live".to_string(),
+                    transition: Some("Fade".to_string()),
+                    duration: Some(300),
+                }
+            ),
+            _ => panic!("Expected Scene::TransitionOverride command"),
+        }
+
+        assert!(
+            Cli::try_parse_from(["obs-cmd", "scene", "transition-override", "set", "Live"])
+                .is_err()
+        );
+
+        let cli = Cli::try_parse_from(["obs-cmd", "scene", "transition-override", "clear", "Live"])
+            .unwrap();
+        match cli.command {
+            Commands::Scene(Scene::TransitionOverride(TransitionOverride::Clear {
+                scene_name,
+            })) => {
+                let handler = ClearTransitionOverrideHandler {
+                    scene_name,
+                    server: ObsWebsocket {
+                        hostname: "localhost".to_string(),
+                        port: 4455,
+                        password: None,
+                    },
+                };
+                assert_eq!(handler.description(), "Clear scene transition override");
+            }
+            _ => panic!("Expected Scene::TransitionOverride::Clear command"),
+        }
+    }
+
     #[tokio::test]
     async fn test_scene_list_command() {
         let command = Commands::Scene(Scene::List);
@@ -467,88 +692,721 @@ mod tests {
 
     #[tokio::test]
     async fn test_audio_mute_command() {
-        let command = Commands::Audio {
-            command: "mute".to_string(),
-            device: "Mic/Aux".to_string(),
-        };
+        let cli = Cli::try_parse_from(["obs-cmd", "audio", "mute", "Mic/Aux"]).unwrap();
 
-        match command {
-            Commands::Audio { command, device } => {
-                assert_eq!(command, "mute");
+        match cli.command {
+            Commands::Audio(Audio::Mute { device }) => {
                 assert_eq!(device, "Mic/Aux");
             }
-            _ => panic!("Expected Audio command"),
+            _ => panic!("Expected Audio::Mute command"),
         }
     }
 
     #[tokio::test]
     async fn test_audio_unmute_command() {
-        let command = Commands::Audio {
-            command: "unmute".to_string(),
-            device: "Mic/Aux".to_string(),
-        };
+        let cli = Cli::try_parse_from(["obs-cmd", "audio", "unmute", "Mic/Aux"]).unwrap();
 
-        match command {
-            Commands::Audio { command, device } => {
-                assert_eq!(command, "unmute");
+        match cli.command {
+            Commands::Audio(Audio::Unmute { device }) => {
                 assert_eq!(device, "Mic/Aux");
             }
-            _ => panic!("Expected Audio command"),
+            _ => panic!("Expected Audio::Unmute command"),
         }
     }
 
     #[tokio::test]
     async fn test_audio_toggle_command() {
-        let command = Commands::Audio {
-            command: "toggle".to_string(),
-            device: "Mic/Aux".to_string(),
-        };
+        let cli = Cli::try_parse_from(["obs-cmd", "audio", "toggle", "Mic/Aux"]).unwrap();
 
-        match command {
-            Commands::Audio { command, device } => {
-                assert_eq!(command, "toggle");
+        match cli.command {
+            Commands::Audio(Audio::Toggle { device }) => {
                 assert_eq!(device, "Mic/Aux");
             }
-            _ => panic!("Expected Audio command"),
+            _ => panic!("Expected Audio::Toggle command"),
         }
     }
 
     #[tokio::test]
     async fn test_filter_enable_command() {
-        let command = Commands::Filter {
-            command: "enable".to_string(),
-            source: "Camera".to_string(),
-            filter: "Color Correction".to_string(),
+        let command = Commands::Filter(Filter::Enable(FilterTarget {
+            source: Some("Camera".to_string()),
+            filter: Some("Color Correction".to_string()),
+            source_glob: None,
+            filter_glob: None,
+            dry_run: false,
+        }));
+
+        match command {
+            Commands::Filter(Filter::Enable(target)) => {
+                assert_eq!(target.source, Some("Camera".to_string()));
+                assert_eq!(target.filter, Some("Color Correction".to_string()));
+            }
+            _ => panic!("Expected Filter::Enable command"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_filter_glob_command_parsing() {
+        let cli = Cli::try_parse_from([
+            "obs-cmd",
+            "filter",
+            "disable",
+            "--source-glob",
+            "Cam*",
+            "--filter-glob",
+            "Blur*",
+            "--dry-run",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Commands::Filter(Filter::Disable(target)) => {
+                assert_eq!(target.source, None);
+                assert_eq!(target.filter, None);
+                assert_eq!(target.source_glob, Some("Cam*".to_string()));
+                assert_eq!(target.filter_glob, Some("Blur*".to_string()));
+                assert!(target.dry_run);
+            }
+            _ => panic!("Expected Filter::Disable command"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_filter_animate_command_parsing() {
+        let cli = Cli::try_parse_from([
+            "obs-cmd",
+            "filter",
+            "animate",
+            "Mic",
+            "Gain",
+            "db",
+            "--to",
+            "-6",
+            "--duration",
+            "2s",
+            "--easing",
+            "ease-in-out",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Commands::Filter(Filter::Animate(animation)) => {
+                assert_eq!(animation.source, "Mic");
+                assert_eq!(animation.filter, "Gain");
+                assert_eq!(animation.setting, "db");
+                assert_eq!(animation.to, -6.0);
+                assert_eq!(animation.from, None);
+                assert_eq!(animation.duration, std::time::Duration::from_secs(2));
+                assert_eq!(animation.easing, Easing::EaseInOut);
+                assert_eq!(animation.fps, 30);
+                assert_eq!(animation.on_abort, AbortSnap::End);
+            }
+            _ => panic!("Expected Filter::Animate command"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_filter_preset_commands() {
+        let cli = Cli::try_parse_from([
+            "obs-cmd",
+            "filter",
+            "preset",
+            "apply",
+            "Mic/Aux",
+            "podcast-mic",
+            "--replace",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Filter(Filter::Preset(preset)) => assert_eq!(
+                preset,
+                FilterPreset::Apply {
+                    source: "Mic/Aux".to_string(),
+                    preset: "podcast-mic".to_string(),
+                    replace: true,
+                }
+            ),
+            _ => panic!("Expected Filter::Preset command"),
+        }
+
+        let handler = FilterHandler {
+            action: Filter::Preset(FilterPreset::Save {
+                source: "Mic/Aux".to_string(),
+                name: "podcast-mic".to_string(),
+            }),
         };
+        assert_eq!(handler.description(), "Save filter preset");
+    }
 
-        match command {
-            Commands::Filter {
-                command,
-                source,
-                filter,
-            } => {
-                assert_eq!(command, "enable");
-                assert_eq!(source, "Camera");
-                assert_eq!(filter, "Color Correction");
+    #[tokio::test]
+    async fn test_filter_command_requires_target() {
+        assert!(Cli::try_parse_from(["obs-cmd", "filter", "toggle"]).is_err());
+        assert!(
+            Cli::try_parse_from(["obs-cmd", "filter", "toggle", "--source-glob", "Cam*"]).is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scene_item_list_command() {
+        let cli = Cli::try_parse_from([
+            "obs-cmd",
+            "scene-item",
+            "list",
+            "Main",
+            "--recursive",
+            "--long",
+            "--expand-nested",
+            "--json",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Commands::SceneItem(SceneItem::List {
+                scene,
+                recursive,
+                long,
+                expand_nested,
+                json,
+            }) => {
+                assert_eq!(scene, "Main");
+                assert!(recursive && long && expand_nested && json);
             }
-            _ => panic!("Expected Filter command"),
+            _ => panic!("Expected SceneItem::List command"),
         }
     }
 
     #[tokio::test]
     async fn test_scene_item_enable_command() {
         let command = Commands::SceneItem(SceneItem::Enable {
-            scene: "Main Scene".to_string(),
-            source: "Webcam".to_string(),
+            target: ItemSelector {
+                scene: Some("Main Scene".to_string()),
+                source: Some("Webcam".to_string()),
+                pattern: None,
+                regex: false,
+                all_scenes: false,
+            },
+            exclusive: false,
         });
 
         match command {
-            Commands::SceneItem(SceneItem::Enable { scene, source }) => {
-                assert_eq!(scene, "Main Scene");
-                assert_eq!(source, "Webcam");
+            Commands::SceneItem(SceneItem::Enable { target, .. }) => {
+                assert_eq!(target.scene, Some("Main Scene".to_string()));
+                assert_eq!(target.source, Some("Webcam".to_string()));
+            }
+            _ => panic!("Expected SceneItem::Enable command"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scene_item_bulk_selectors() {
+        let cli = Cli::try_parse_from([
+            "obs-cmd",
+            "scene-item",
+            "enable",
+            "Main",
+            "--match",
+            "Alert*",
+            "--exclusive",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::SceneItem(SceneItem::Enable { target, exclusive }) => {
+                assert_eq!(target.scene, Some("Main".to_string()));
+                assert_eq!(target.source, None);
+                assert_eq!(target.pattern, Some("Alert*".to_string()));
+                assert!(exclusive);
             }
             _ => panic!("Expected SceneItem::Enable command"),
         }
+
+        let cli = Cli::try_parse_from([
+            "obs-cmd",
+            "scene-item",
+            "lock",
+            "--all-scenes",
+            "--match",
+            "^Border",
+            "--regex",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::SceneItem(SceneItem::Lock(target)) => {
+                assert_eq!(target.scene, None);
+                assert!(target.all_scenes && target.regex);
+            }
+            _ => panic!("Expected SceneItem::Lock command"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "obs-cmd",
+            "scene-item",
+            "set-blend-mode",
+            "Main",
+            "--match",
+            "Overlay*",
+            "--mode",
+            "screen",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::SceneItem(SceneItem::SetBlendMode { target, blend_mode }) => {
+                assert_eq!(target.scene, Some("Main".to_string()));
+                assert_eq!(blend_mode, "screen");
+            }
+            _ => panic!("Expected SceneItem::SetBlendMode command"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "obs-cmd",
+            "scene-item",
+            "set-blend-mode",
+            "--all-scenes",
+            "--match",
+            "Overlay*",
+            "--mode",
+            "screen",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::SceneItem(SceneItem::SetBlendMode { target, blend_mode }) => {
+                assert!(target.all_scenes);
+                assert_eq!(target.scene, None);
+                assert_eq!(blend_mode, "screen");
+            }
+            _ => panic!("Expected SceneItem::SetBlendMode command"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "obs-cmd",
+            "scene-item",
+            "set-blend-mode",
+            "Main",
+            "Overlay",
+            "--mode",
+            "screen",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::SceneItem(SceneItem::SetBlendMode { target, blend_mode }) => {
+                assert_eq!(target.source, Some("Overlay".to_string()));
+                assert_eq!(blend_mode, "screen");
+            }
+            _ => panic!("Expected SceneItem::SetBlendMode command"),
+        }
+
+        // The blend mode is never taken from a positional argument
+        for args in [
+            &["set-blend-mode", "Main", "Cam"][..],
+            &["set-blend-mode", "Main", "Cam", "screen"][..],
+            &["set-blend-mode", "--all-scenes", "--match", "Cam*"][..],
+        ] {
+            let args = ["obs-cmd", "scene-item"].iter().chain(args);
+            assert!(Cli::try_parse_from(args).is_err());
+        }
+
+        assert!(Cli::try_parse_from(["obs-cmd", "scene-item", "disable", "Main"]).is_err());
+        assert!(Cli::try_parse_from([
+            "obs-cmd",
+            "scene-item",
+            "enable",
+            "Main",
+            "Cam",
+            "--exclusive"
+        ])
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_scene_item_relative_transform() {
+        let cli = Cli::try_parse_from([
+            "obs-cmd",
+            "scene-item",
+            "set-transform",
+            "Main",
+            "Camera",
+            "--position-x",
+            "+20",
+            "--position-y",
+            "50%",
+            "--scale-x",
+            "*1.1",
+            "--rotation",
+            "-15",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::SceneItem(SceneItem::SetTransform {
+                position_x,
+                position_y,
+                scale_x,
+                rotation,
+                anchor,
+                ..
+            }) => {
+                assert_eq!(position_x, Some(TransformValue::Offset(20.0)));
+                assert_eq!(position_y, Some(TransformValue::Percent(50.0)));
+                assert_eq!(scale_x, Some(TransformValue::Factor(1.1)));
+                assert_eq!(rotation, Some(TransformValue::Offset(-15.0)));
+                assert_eq!(anchor, None);
+            }
+            _ => panic!("Expected SceneItem::SetTransform command"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "obs-cmd",
+            "scene-item",
+            "set-transform",
+            "Main",
+            "Camera",
+            "--anchor",
+            "bottom-right",
+            "--margin",
+            "40",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::SceneItem(SceneItem::SetTransform { anchor, margin, .. }) => {
+                assert_eq!(anchor, Some(ItemAlignment::BottomRight));
+                assert_eq!(margin, 40.0);
+            }
+            _ => panic!("Expected SceneItem::SetTransform command"),
+        }
+
+        // An anchor replaces the position
+        assert!(Cli::try_parse_from([
+            "obs-cmd",
+            "scene-item",
+            "set-transform",
+            "Main",
+            "Camera",
+            "--anchor",
+            "top",
+            "--position-x",
+            "10",
+        ])
+        .is_err());
+        assert!(Cli::try_parse_from([
+            "obs-cmd",
+            "scene-item",
+            "set-transform",
+            "Main",
+            "Camera",
+            "--margin",
+            "40",
+        ])
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_scene_item_animate_command() {
+        let cli = Cli::try_parse_from([
+            "obs-cmd",
+            "scene-item",
+            "animate",
+            "Main",
+            "Camera",
+            "Group/Logo",
+            "--to-position",
+            "100,-200",
+            "--to-scale",
+            "0.5",
+            "--to-rotation",
+            "-10",
+            "--to-crop",
+            "0,10,0,10",
+            "--duration",
+            "1.5s",
+            "--easing",
+            "cubic-out",
+            "--fps",
+            "60",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::SceneItem(SceneItem::Animate(animation)) => {
+                assert_eq!(animation.scene, "Main");
+                assert_eq!(animation.items, vec!["Camera", "Group/Logo"]);
+                assert_eq!(animation.to_position, Some((100.0, -200.0)));
+                assert_eq!(animation.to_scale, Some((0.5, 0.5)));
+                assert_eq!(animation.to_rotation, Some(-10.0));
+                assert_eq!(animation.to_crop, Some([0, 10, 0, 10]));
+                assert_eq!(animation.duration, std::time::Duration::from_millis(1500));
+                assert_eq!(animation.easing, Easing::CubicOut);
+                assert_eq!(animation.fps, 60);
+            }
+            _ => panic!("Expected SceneItem::Animate command"),
+        }
+
+        // At least one target is required
+        assert!(
+            Cli::try_parse_from(["obs-cmd", "scene-item", "animate", "Main", "Camera"]).is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scene_item_layout_commands() {
+        let cli = Cli::try_parse_from([
+            "obs-cmd",
+            "scene-item",
+            "layout",
+            "Guests",
+            "grid",
+            "--items",
+            "A,B,C,D",
+            "--gap",
+            "16",
+            "--margin",
+            "32",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::SceneItem(SceneItem::Layout {
+                scene,
+                layout:
+                    Layout::Grid {
+                        items,
+                        columns,
+                        gap,
+                        margin,
+                    },
+            }) => {
+                assert_eq!(scene, "Guests");
+                assert_eq!(items, vec!["A", "B", "C", "D"]);
+                assert_eq!(columns, None);
+                assert_eq!((gap, margin), (16.0, 32.0));
+            }
+            _ => panic!("Expected SceneItem::Layout grid command"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "obs-cmd",
+            "scene-item",
+            "layout",
+            "Guests",
+            "pip",
+            "--main",
+            "A",
+            "--inset",
+            "B",
+            "--corner",
+            "bottom-right",
+            "--size",
+            "25%",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::SceneItem(SceneItem::Layout {
+                layout:
+                    Layout::Pip {
+                        main,
+                        inset,
+                        corner,
+                        size,
+                        ..
+                    },
+                ..
+            }) => {
+                assert_eq!((main.as_str(), inset.as_str()), ("A", "B"));
+                assert_eq!(corner, ItemAlignment::BottomRight);
+                assert_eq!(size, 25.0);
+            }
+            _ => panic!("Expected SceneItem::Layout pip command"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "obs-cmd",
+            "scene-item",
+            "layout",
+            "Guests",
+            "side-by-side",
+            "A",
+            "B",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::SceneItem(SceneItem::Layout {
+                layout: Layout::SideBySide { .. },
+                ..
+            })
+        ));
+
+        let cli = Cli::try_parse_from([
+            "obs-cmd",
+            "scene-item",
+            "layout",
+            "Guests",
+            "save",
+            "interview",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::SceneItem(SceneItem::Layout {
+                layout: Layout::Save { name, items },
+                ..
+            }) => {
+                assert_eq!(name, "interview");
+                assert!(items.is_empty());
+            }
+            _ => panic!("Expected SceneItem::Layout save command"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "obs-cmd",
+            "scene-item",
+            "layout",
+            "Guests",
+            "apply",
+            "interview",
+        ])
+        .unwrap();
+        let handler = SceneItemHandler {
+            action: match cli.command {
+                Commands::SceneItem(action) => action,
+                _ => panic!("Expected SceneItem command"),
+            },
+        };
+        assert_eq!(handler.description(), "Apply saved scene item layout");
+
+        assert!(
+            Cli::try_parse_from(["obs-cmd", "scene-item", "layout", "Guests", "grid"]).is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scene_item_create_and_copy_commands() {
+        let cli = Cli::try_parse_from([
+            "obs-cmd",
+            "scene-item",
+            "create",
+            "Main",
+            "Camera",
+            "--group",
+            "Guests",
+            "--position-x",
+            "-100",
+            "--scale-x",
+            "0.5",
+            "--alignment",
+            "center",
+            "--index",
+            "2",
+            "--mode",
+            "screen",
+            "--locked",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::SceneItem(SceneItem::Create {
+                scene,
+                source,
+                enabled,
+                group,
+                position_x,
+                scale_x,
+                alignment,
+                index,
+                blend_mode,
+                locked,
+                ..
+            }) => {
+                assert_eq!((scene.as_str(), source.as_str()), ("Main", "Camera"));
+                assert_eq!(enabled, None);
+                assert_eq!(group.as_deref(), Some("Guests"));
+                assert_eq!(position_x, Some(-100.0));
+                assert_eq!(scale_x, Some(0.5));
+                assert_eq!(alignment, Some(ItemAlignment::Center));
+                assert_eq!(index, Some(2));
+                assert_eq!(blend_mode.as_deref(), Some("screen"));
+                assert!(locked);
+            }
+            _ => panic!("Expected SceneItem::Create command"),
+        }
+        // create and set-blend-mode spell the option the same way
+        assert!(Cli::try_parse_from([
+            "obs-cmd",
+            "scene-item",
+            "create",
+            "Main",
+            "Camera",
+            "--blend-mode",
+            "screen",
+        ])
+        .is_err());
+
+        let cli = Cli::try_parse_from([
+            "obs-cmd",
+            "scene-item",
+            "copy",
+            "Main",
+            "Group/Camera",
+            "Interview",
+            "--keep-transform",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::SceneItem(SceneItem::Copy {
+                from_scene,
+                source,
+                to_scene,
+                keep_transform,
+            }) => {
+                assert_eq!(from_scene, "Main");
+                assert_eq!(source, "Group/Camera");
+                assert_eq!(to_scene, "Interview");
+                assert!(keep_transform);
+            }
+            _ => panic!("Expected SceneItem::Copy command"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scene_item_bounds_commands() {
+        let cli = Cli::try_parse_from([
+            "obs-cmd",
+            "scene-item",
+            "set-transform",
+            "Main",
+            "Cam",
+            "--alignment",
+            "top-left",
+            "--bounds-type",
+            "scale-inner",
+            "--bounds-width",
+            "1920",
+            "--bounds-height",
+            "1080",
+            "--bounds-alignment",
+            "center",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::SceneItem(SceneItem::SetTransform {
+                alignment,
+                bounds_type,
+                bounds_width,
+                bounds_height,
+                bounds_alignment,
+                ..
+            }) => {
+                assert_eq!(alignment, Some(ItemAlignment::TopLeft));
+                assert_eq!(bounds_type, Some(ItemBoundsType::ScaleInner));
+                assert_eq!(bounds_width, Some(TransformValue::Absolute(1920.0)));
+                assert_eq!(bounds_height, Some(TransformValue::Absolute(1080.0)));
+                assert_eq!(bounds_alignment, Some(ItemAlignment::Center));
+            }
+            _ => panic!("Expected SceneItem::SetTransform command"),
+        }
+
+        let cli =
+            Cli::try_parse_from(["obs-cmd", "scene-item", "fit", "Main", "Group/Cam"]).unwrap();
+        match cli.command {
+            Commands::SceneItem(SceneItem::Fit { scene, source }) => {
+                assert_eq!(scene, "Main");
+                assert_eq!(source, "Group/Cam");
+            }
+            _ => panic!("Expected SceneItem::Fit command"),
+        }
     }
 
     #[tokio::test]
@@ -640,6 +1498,240 @@ mod tests {
     }
 
     // Handler description tests (from original src/handlers/handler_tests.rs)
+    #[tokio::test]
+    async fn test_snapshot_export_command() {
+        let cli = Cli::try_parse_from(["obs-cmd", "snapshot", "export"]).unwrap();
+        match cli.command {
+            Commands::Snapshot(Snapshot::Export { output }) => assert_eq!(output, None),
+            _ => panic!("Expected Snapshot::Export command"),
+        }
+
+        let cli =
+            Cli::try_parse_from(["obs-cmd", "snapshot", "export", "-o", "show.json"]).unwrap();
+        match cli.command {
+            Commands::Snapshot(Snapshot::Export { output }) => {
+                assert_eq!(output, Some(PathBuf::from("show.json")))
+            }
+            _ => panic!("Expected Snapshot::Export command"),
+        }
+
+        let handler = SnapshotHandler {
+            action: Snapshot::Export { output: None },
+        };
+        assert_eq!(handler.description(), "Export OBS state snapshot");
+    }
+
+    #[tokio::test]
+    async fn test_writes_document_to_stdout() {
+        for (args, expected) in [
+            (vec!["obs-cmd", "snapshot", "export"], true),
+            (
+                vec!["obs-cmd", "snapshot", "export", "-o", "show.json"],
+                false,
+            ),
+            (
+                vec!["obs-cmd", "scene-item", "list", "Scene", "--json"],
+                true,
+            ),
+            (vec!["obs-cmd", "scene-item", "list", "Scene"], false),
+            (
+                vec!["obs-cmd", "diff", "a.json", "--target", "b.json", "--json"],
+                true,
+            ),
+            (vec!["obs-cmd", "graph"], true),
+            (vec!["obs-cmd", "graph", "--format", "mermaid"], true),
+            (vec!["obs-cmd", "orphans"], false),
+            (vec!["obs-cmd", "scene", "current"], false),
+        ] {
+            let cli = Cli::try_parse_from(&args).unwrap();
+            assert_eq!(
+                writes_document_to_stdout(&cli.command),
+                expected,
+                "{:?}",
+                args
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_apply_command() {
+        let cli = Cli::try_parse_from(["obs-cmd", "apply", "show.yaml"]).unwrap();
+        match cli.command {
+            Commands::Apply { file, yes, prune } => {
+                assert_eq!(file, PathBuf::from("show.yaml"));
+                assert!(!yes);
+                assert!(!prune);
+            }
+            _ => panic!("Expected Apply command"),
+        }
+
+        let cli =
+            Cli::try_parse_from(["obs-cmd", "apply", "show.yaml", "--yes", "--prune"]).unwrap();
+        match cli.command {
+            Commands::Apply { yes, prune, .. } => assert!(yes && prune),
+            _ => panic!("Expected Apply command"),
+        }
+        assert!(Cli::try_parse_from(["obs-cmd", "apply"]).is_err());
+
+        let handler = ApplyHandler {
+            file: PathBuf::from("show.yaml"),
+            yes: false,
+            prune: false,
+        };
+        assert_eq!(handler.description(), "Plan OBS state changes");
+        let handler = ApplyHandler {
+            yes: true,
+            ..handler
+        };
+        assert_eq!(handler.description(), "Apply OBS state file");
+    }
+
+    #[tokio::test]
+    async fn test_lint_command() {
+        let cli = Cli::try_parse_from(["obs-cmd", "lint", "Untitled.json"]).unwrap();
+        match cli.command {
+            Commands::Lint { file } => assert_eq!(file, PathBuf::from("Untitled.json")),
+            _ => panic!("Expected Lint command"),
+        }
+        assert!(Cli::try_parse_from(["obs-cmd", "lint"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_diff_command() {
+        let cli = Cli::try_parse_from(["obs-cmd", "diff", "show.json"]).unwrap();
+        match cli.command {
+            Commands::Diff(args) => {
+                assert_eq!(args.file, Some(PathBuf::from("show.json")));
+                assert!(args.targets.is_empty());
+                assert!(args.needs_default_connection());
+            }
+            _ => panic!("Expected Diff command"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "obs-cmd",
+            "diff",
+            "--target",
+            "studio-a",
+            "--target",
+            "obsws://10.0.0.2:4455/secret",
+            "--json",
+            "--exit-code",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Diff(args) => {
+                assert_eq!(args.file, None);
+                assert_eq!(
+                    args.targets,
+                    vec!["studio-a", "obsws://10.0.0.2:4455/secret"]
+                );
+                assert!(args.json && args.exit_code);
+                assert!(!args.needs_default_connection());
+                let handler = DiffHandler { args };
+                assert_eq!(handler.description(), "Compare OBS states");
+            }
+            _ => panic!("Expected Diff command"),
+        }
+
+        let cli =
+            Cli::try_parse_from(["obs-cmd", "diff", "show.yaml", "--target", "backup"]).unwrap();
+        match cli.command {
+            Commands::Diff(args) => assert!(!args.needs_default_connection()),
+            _ => panic!("Expected Diff command"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_graph_command() {
+        let cli = Cli::try_parse_from(["obs-cmd", "graph"]).unwrap();
+        match cli.command {
+            Commands::Graph { format } => assert_eq!(format, GraphFormat::Dot),
+            _ => panic!("Expected Graph command"),
+        }
+
+        let cli = Cli::try_parse_from(["obs-cmd", "graph", "--format", "mermaid"]).unwrap();
+        match cli.command {
+            Commands::Graph { format } => {
+                assert_eq!(format, GraphFormat::Mermaid);
+                let handler = GraphHandler { format };
+                assert_eq!(handler.description(), "Print source usage graph");
+            }
+            _ => panic!("Expected Graph command"),
+        }
+        assert!(Cli::try_parse_from(["obs-cmd", "graph", "--format", "svg"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_graph_output_is_only_the_graph() {
+        let graph = SourceGraph::new(&[], &[("Camera".to_string(), "v4l2_input".to_string())]);
+        for (format, header) in [
+            (GraphFormat::Dot, "digraph obs {\n"),
+            (GraphFormat::Mermaid, "graph LR\n"),
+        ] {
+            let command = Commands::Graph { format };
+            // The status line goes to stderr, so stdout is only the graph
+            assert!(writes_document_to_stdout(&command));
+            let output = GraphHandler { format }.render(&graph);
+            assert!(output.starts_with(header), "{}", output);
+            assert!(!output.contains("Executing"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_orphans_command() {
+        let cli = Cli::try_parse_from(["obs-cmd", "orphans"]).unwrap();
+        match cli.command {
+            Commands::Orphans { remove } => assert!(!remove),
+            _ => panic!("Expected Orphans command"),
+        }
+
+        let cli = Cli::try_parse_from(["obs-cmd", "orphans", "--remove"]).unwrap();
+        match cli.command {
+            Commands::Orphans { remove } => {
+                assert!(remove);
+                let handler = OrphansHandler { remove };
+                assert_eq!(handler.description(), "Remove unused inputs");
+            }
+            _ => panic!("Expected Orphans command"),
+        }
+        let handler = OrphansHandler { remove: false };
+        assert_eq!(handler.description(), "List unused inputs");
+    }
+
+    #[tokio::test]
+    async fn test_rename_command() {
+        let cli = Cli::try_parse_from([
+            "obs-cmd",
+            "rename",
+            "--inputs",
+            "--scenes",
+            r"s/^Cam (\d)/Camera-$1/",
+            "--dry-run",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Rename(args) => {
+                assert_eq!(
+                    args,
+                    RenameArgs {
+                        expression: r"s/^Cam (\d)/Camera-$1/".to_string(),
+                        inputs: true,
+                        scenes: true,
+                        dry_run: true,
+                    }
+                );
+                let handler = RenameHandler { args };
+                assert_eq!(handler.description(), "Preview renames");
+            }
+            _ => panic!("Expected Rename command"),
+        }
+
+        // At least one of --inputs and --scenes is required
+        assert!(Cli::try_parse_from(["obs-cmd", "rename", "s/a/b/"]).is_err());
+        assert!(Cli::try_parse_from(["obs-cmd", "rename", "--scenes", "s/a/b/"]).is_ok());
+    }
+
     #[tokio::test]
     async fn test_recording_handler_description() {
         let handler = RecordingHandler {
@@ -752,18 +1844,89 @@ mod tests {
     #[tokio::test]
     async fn test_audio_handler_description() {
         let handler = AudioHandler {
-            command: "mute".to_string(),
-            device: "Mic/Aux".to_string(),
+            action: Audio::Mute {
+                device: "Mic/Aux".to_string(),
+            },
         };
         assert_eq!(handler.description(), "Mute audio device");
     }
 
+    #[tokio::test]
+    async fn test_audio_volume_command() {
+        let cli = Cli::try_parse_from(["obs-cmd", "audio", "volume", "Mic/Aux"]).unwrap();
+        match cli.command {
+            Commands::Audio(action) => {
+                assert_eq!(
+                    action,
+                    Audio::Volume {
+                        device: "Mic/Aux".to_string(),
+                        value: None,
+                    }
+                );
+                let handler = AudioHandler { action };
+                assert_eq!(handler.description(), "Get audio device volume");
+            }
+            _ => panic!("Expected Audio::Volume command"),
+        }
+
+        // Negative values are volumes, not flags
+        for (text, value) in [
+            ("-6dB", VolumeValue::Db(-6.0)),
+            ("-10%", VolumeValue::PercentStep(-10.0)),
+            ("+3dB", VolumeValue::DbStep(3.0)),
+        ] {
+            let cli = Cli::try_parse_from(["obs-cmd", "audio", "volume", "Mic/Aux", text]).unwrap();
+            match cli.command {
+                Commands::Audio(Audio::Volume {
+                    device,
+                    value: Some(parsed),
+                }) => {
+                    assert_eq!(device, "Mic/Aux");
+                    assert_eq!(parsed, value);
+                }
+                _ => panic!("Expected Audio::Volume command"),
+            }
+        }
+        let handler = AudioHandler {
+            action: Audio::Volume {
+                device: "Mic/Aux".to_string(),
+                value: Some(VolumeValue::Percent(75.0)),
+            },
+        };
+        assert_eq!(handler.description(), "Set audio device volume");
+        assert!(Cli::try_parse_from(["obs-cmd", "audio", "volume", "Mic/Aux", "loud"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_audio_mixer_command() {
+        let cli = Cli::try_parse_from(["obs-cmd", "audio", "mixer"]).unwrap();
+        match cli.command {
+            Commands::Audio(Audio::Mixer { watch }) => assert!(!watch),
+            _ => panic!("Expected Audio::Mixer command"),
+        }
+
+        let cli = Cli::try_parse_from(["obs-cmd", "audio", "mixer", "--watch"]).unwrap();
+        match cli.command {
+            Commands::Audio(action) => {
+                assert_eq!(action, Audio::Mixer { watch: true });
+                let handler = AudioHandler { action };
+                assert_eq!(handler.description(), "Show audio mixer");
+            }
+            _ => panic!("Expected Audio::Mixer command"),
+        }
+        assert!(Cli::try_parse_from(["obs-cmd", "audio", "louder", "Mic/Aux"]).is_err());
+    }
+
     #[tokio::test]
     async fn test_filter_handler_description() {
         let handler = FilterHandler {
-            command: "toggle".to_string(),
-            source: "Camera".to_string(),
-            filter: "Color Correction".to_string(),
+            action: Filter::Toggle(FilterTarget {
+                source: Some("Camera".to_string()),
+                filter: Some("Color Correction".to_string()),
+                source_glob: None,
+                filter_glob: None,
+                dry_run: false,
+            }),
         };
         assert_eq!(handler.description(), "Toggle filter");
     }
@@ -1517,6 +2680,77 @@ mod tests {
         assert_eq!(handler.description(), "List special inputs");
     }
 
+    #[tokio::test]
+    async fn test_transition_tbar_slide_command() {
+        let cli = Cli::try_parse_from([
+            "obs-cmd",
+            "transition",
+            "tbar",
+            "--from",
+            "0",
+            "--to",
+            "1",
+            "--duration",
+            "3s",
+            "--release",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Commands::Transition(Transition::Tbar {
+                position,
+                from,
+                to,
+                duration,
+                release,
+                ..
+            }) => {
+                assert_eq!(position, None);
+                assert_eq!(from, 0.0);
+                assert_eq!(to, Some(1.0));
+                assert_eq!(duration, std::time::Duration::from_secs(3));
+                assert!(release);
+            }
+            _ => panic!("Expected Transition::Tbar command"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transition_tbar_rejects_out_of_range_position() {
+        assert!(Cli::try_parse_from(["obs-cmd", "transition", "tbar", "1.5"]).is_err());
+        assert!(Cli::try_parse_from(["obs-cmd", "transition", "tbar"]).is_err());
+        assert!(Cli::try_parse_from(["obs-cmd", "transition", "tbar", "0.5"]).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_transition_handler_description() {
+        let handler = TransitionHandler {
+            action: Transition::Settings {
+                settings: r#"{"color": 4278190080}"#.to_string(),
+                replace: false,
+            },
+        };
+        assert_eq!(handler.description(), "Set current transition settings");
+
+        let handler = TransitionHandler {
+            action: Transition::Kinds,
+        };
+        assert_eq!(handler.description(), "List transition kinds");
+    }
+
+    #[tokio::test]
+    async fn test_glob_match() {
+        assert!(glob_match("Cam*", "Cam 1"));
+        assert!(glob_match("Cam*", "Cam"));
+        assert!(glob_match("*Blur*", "Heavy Blur 2"));
+        assert!(glob_match("Cam ?", "Cam 3"));
+        assert!(glob_match("[nested] *", "[nested] Lower Third"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("Cam*", "Webcam"));
+        assert!(!glob_match("Cam ?", "Cam 10"));
+        assert!(!glob_match("Blur", "Blur 2"));
+    }
+
     // Integration tests would go here in a real implementation
     // These would test the actual handler logic with mock OBS responses
 }
@@ -1,6 +1,8 @@
 use crate::cli::{Input, MuteAction};
 use crate::error::{ObsCmdError, Result};
+use crate::handlers::audio::volume_summary;
 use crate::handlers::CommandHandler;
+use obws::requests::inputs::Volume;
 use obws::Client;
 
 /// Handler for input management commands
@@ -81,8 +83,12 @@ impl CommandHandler for InputCmdHandler {
                 set,
             } => {
                 if *get {
-                    println!("Getting volume for '{}'", input_name);
-                    println!("Volume get is experimental");
+                    let volume = client
+                        .inputs()
+                        .volume(input_name.as_str().into())
+                        .await
+                        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+                    println!("Volume of '{}': {}", input_name, volume_summary(volume.mul));
                 } else if let Some(new_volume) = set {
                     if !(0.0..=1.0).contains(new_volume) {
                         return Err(ObsCmdError::InvalidVolume {
@@ -90,8 +96,16 @@ impl CommandHandler for InputCmdHandler {
                         });
                     }
 
-                    println!("Setting volume for '{}' to {:.2}", input_name, new_volume);
-                    println!("Volume set is experimental");
+                    client
+                        .inputs()
+                        .set_volume(input_name.as_str().into(), Volume::Mul(*new_volume as f32))
+                        .await
+                        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+                    println!(
+                        "Set volume of '{}' to {}",
+                        input_name,
+                        volume_summary(*new_volume as f32)
+                    );
                 }
             }
 
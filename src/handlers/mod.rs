@@ -1,17 +1,23 @@
+pub mod apply;
 pub mod audio;
 pub mod config;
+pub mod diff;
 pub mod filters;
 pub mod general;
+pub mod graph;
 pub mod handler_tests;
 pub mod inputs;
 pub mod media;
 pub mod recording;
+pub mod rename;
 pub mod replay_buffer;
 pub mod scene_collections;
 pub mod scene_items;
 pub mod scenes;
+pub mod snapshot;
 pub mod sources;
 pub mod streaming;
+pub mod transitions;
 pub mod ui;
 pub mod virtual_camera;
 
@@ -44,3 +50,38 @@ pub fn validate_monitor_index(
     }
     Ok(())
 }
+
+/// Utility function for matching a name against a glob pattern
+///
+/// `*` matches any run of characters (including none) and `?` matches exactly
+/// one character. Every other character, including `[` and `]`, is literal.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    backtrack = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
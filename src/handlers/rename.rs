@@ -0,0 +1,100 @@
+use crate::cli::RenameArgs;
+use crate::error::{ObsCmdError, Result};
+use crate::handlers::CommandHandler;
+use crate::rename::{order_renames, plan_renames, SourceType, Substitution};
+use obws::requests::inputs::InputId;
+use obws::requests::scenes::SceneId;
+use obws::Client;
+
+/// Handler for the `rename` command
+pub struct RenameHandler {
+    pub args: RenameArgs,
+}
+
+#[async_trait::async_trait]
+impl CommandHandler for RenameHandler {
+    async fn execute(&self, client: &Client) -> Result<()> {
+        let substitution = Substitution::parse(&self.args.expression)?;
+
+        let scenes: Vec<String> = client
+            .scenes()
+            .list()
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?
+            .scenes
+            .into_iter()
+            .map(|scene| scene.id.name)
+            .collect();
+        let inputs: Vec<String> = client
+            .inputs()
+            .list(None)
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?
+            .into_iter()
+            .map(|input| input.id.name)
+            .collect();
+        let groups = client
+            .scenes()
+            .list_groups()
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+
+        let mut candidates = Vec::new();
+        if self.args.inputs {
+            candidates.extend(inputs.iter().map(|name| (SourceType::Input, name.clone())));
+        }
+        if self.args.scenes {
+            candidates.extend(scenes.iter().map(|name| (SourceType::Scene, name.clone())));
+        }
+        let existing: Vec<String> = scenes.into_iter().chain(inputs).chain(groups).collect();
+
+        let renames = plan_renames(&substitution, &candidates, &existing)?;
+        if renames.is_empty() {
+            println!("No names are changed by {}", self.args.expression);
+            return Ok(());
+        }
+
+        let width = renames
+            .iter()
+            .map(|rename| rename.from.chars().count())
+            .max()
+            .unwrap_or_default();
+        for rename in &renames {
+            println!(
+                "{:<5}  {:<width$}  ->  {}",
+                rename.source_type.to_string(),
+                rename.from,
+                rename.to
+            );
+        }
+        if self.args.dry_run {
+            println!("\nDry run: {} rename(s) not applied", renames.len());
+            return Ok(());
+        }
+
+        for step in order_renames(&renames, &existing) {
+            match step.source_type {
+                SourceType::Input => client
+                    .inputs()
+                    .set_name(InputId::Name(&step.from), &step.to)
+                    .await
+                    .map_err(|e| ObsCmdError::ConnectionError(e))?,
+                SourceType::Scene => client
+                    .scenes()
+                    .set_name(SceneId::Name(&step.from), &step.to)
+                    .await
+                    .map_err(|e| ObsCmdError::ConnectionError(e))?,
+            }
+        }
+        println!("\nRenamed {} source(s)", renames.len());
+        Ok(())
+    }
+
+    fn description(&self) -> &'static str {
+        if self.args.dry_run {
+            "Preview renames"
+        } else {
+            "Rename sources"
+        }
+    }
+}
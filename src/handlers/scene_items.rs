@@ -1,14 +1,25 @@
-use crate::cli::SceneItem;
+use crate::animation::{lerp, Timeline};
+use crate::cli::{
+    AbortSnap, ItemAlignment, ItemAnimation, ItemBoundsType, ItemSelector, Layout, SceneItem,
+    TransformValue,
+};
 use crate::error::{ObsCmdError, Result};
-use crate::handlers::CommandHandler;
-use obws::common::BlendMode;
+use crate::handlers::{glob_match, CommandHandler};
+use crate::layout::{fit_in_cell, grid_cells, inset_cell, Rect};
+use crate::state::TransformState;
+use crate::user_config::{LayoutEntry, UserConfig};
+use futures_util::future::{try_join_all, BoxFuture, FutureExt};
+use obws::common::{Alignment, BlendMode, BoundsType};
 use obws::requests::scene_items::{
-    CreateSceneItem, Crop, Duplicate, Id as IdItem, Position, Scale, SceneItemTransform,
-    SetBlendMode, SetEnabled as SetEnabledItem, SetIndex, SetLocked, SetTransform,
+    Bounds, CreateSceneItem, Crop, Duplicate, Position, Scale, SceneItemTransform, SetBlendMode,
+    SetEnabled as SetEnabledItem, SetIndex, SetLocked, SetTransform, Source as ItemSource,
 };
 use obws::requests::scenes::SceneId;
 use obws::requests::sources::SourceId;
+use obws::responses::scene_items::{SceneItemTransform as ItemTransform, SourceType};
 use obws::Client;
+use regex::Regex;
+use serde::Serialize;
 
 /// Handler for scene item commands
 pub struct SceneItemHandler {
@@ -18,81 +29,161 @@ pub struct SceneItemHandler {
 #[async_trait::async_trait]
 impl CommandHandler for SceneItemHandler {
     async fn execute(&self, client: &Client) -> Result<()> {
+        if let Some((target, change, exclusive)) = self.bulk_change()? {
+            return change_matching_items(client, &target, change, exclusive).await;
+        }
+
         match &self.action {
-            SceneItem::List { scene } => {
-                let items = client
-                    .scene_items()
-                    .list(SceneId::Name(scene))
-                    .await
-                    .map_err(|e| ObsCmdError::ConnectionError(e))?;
+            SceneItem::List {
+                scene,
+                recursive,
+                long,
+                expand_nested,
+                json,
+            } => {
+                let options = TreeOptions {
+                    recursive: *recursive,
+                    long: *long,
+                    expand_nested: *expand_nested,
+                };
+                let items =
+                    fetch_item_tree(client, scene.clone(), false, options, vec![scene.clone()])
+                        .await?;
 
-                println!("Scene items in '{}':", scene);
-                for item in items {
-                    println!(
-                        "  - ID: {}, Source: '{}', Index: {}",
-                        item.id, item.source_name, item.index
-                    );
+                if *json {
+                    println!("{}", serde_json::to_string_pretty(&items)?);
+                } else {
+                    println!("Scene items in '{}':", scene);
+                    print_item_tree(&items, 1);
                 }
             }
+            SceneItem::Animate(animation) => return animate_scene_items(client, animation).await,
+            SceneItem::Layout { scene, layout } => return run_layout(client, scene, layout).await,
             SceneItem::Create {
                 scene,
                 source,
                 enabled,
+                group,
+                position_x,
+                position_y,
+                scale_x,
+                scale_y,
+                rotation,
+                alignment,
+                index,
+                blend_mode,
+                locked,
             } => {
+                let blend_mode = blend_mode.as_deref().map(parse_blend_mode).transpose()?;
+                let transform = SceneItemTransform {
+                    position: if position_x.is_some() || position_y.is_some() {
+                        Some(Position {
+                            x: position_x.map(|x| x as f32),
+                            y: position_y.map(|y| y as f32),
+                        })
+                    } else {
+                        None
+                    },
+                    scale: if scale_x.is_some() || scale_y.is_some() {
+                        Some(Scale {
+                            x: scale_x.map(|x| x as f32),
+                            y: scale_y.map(|y| y as f32),
+                        })
+                    } else {
+                        None
+                    },
+                    rotation: rotation.map(|r| r as f32),
+                    alignment: alignment.map(Alignment::from),
+                    ..Default::default()
+                };
+                let has_transform = transform.position.is_some()
+                    || transform.scale.is_some()
+                    || transform.rotation.is_some()
+                    || transform.alignment.is_some();
+                let has_setup = has_transform || index.is_some() || blend_mode.is_some() || *locked;
+
+                if let Some(group) = group {
+                    let in_scene = client
+                        .scene_items()
+                        .list(SceneId::Name(scene))
+                        .await
+                        .map_err(|e| ObsCmdError::ConnectionError(e))?
+                        .iter()
+                        .any(|item| item.source_name == *group && item.is_group == Some(true));
+                    if !in_scene {
+                        return Err(ObsCmdError::GroupNotInScene {
+                            scene: scene.clone(),
+                            group: group.clone(),
+                        });
+                    }
+                }
+
+                let container = group.as_deref().unwrap_or(scene);
                 let item_id = client
                     .scene_items()
                     .create(CreateSceneItem {
-                        scene: SceneId::Name(scene),
+                        scene: SceneId::Name(container),
                         source: SourceId::Name(source),
-                        enabled: *enabled,
+                        // Stay hidden while the options are applied
+                        enabled: if has_setup { Some(false) } else { *enabled },
                     })
                     .await
                     .map_err(|e| ObsCmdError::ConnectionError(e))?;
+                let item = ResolvedItem {
+                    container: container.to_string(),
+                    in_group: group.is_some(),
+                    item_id,
+                };
+
+                if has_setup {
+                    let setup = setup_new_item(
+                        client,
+                        &item,
+                        has_transform.then_some(transform),
+                        *index,
+                        blend_mode,
+                        *locked,
+                        enabled.unwrap_or(true),
+                    )
+                    .await;
+                    if let Err(e) = setup {
+                        client
+                            .scene_items()
+                            .remove(SceneId::Name(&item.container), item.item_id)
+                            .await
+                            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+                        return Err(e);
+                    }
+                }
 
                 println!(
-                    "Created scene item '{}' in scene '{}' with ID: {}",
-                    source, scene, item_id
+                    "Created scene item '{}' in {} with ID: {}",
+                    source,
+                    item.location(scene),
+                    item_id
                 );
             }
             SceneItem::Remove { scene, source } => {
-                // Check if source path contains a group separator "/"
-                let target_scene = if let Some(slash_pos) = source.find('/') {
-                    &source[..slash_pos]
-                } else {
-                    scene
-                };
-
-                let item_id = get_scene_item_id(client, target_scene, source).await?;
+                let item = resolve_item(client, scene, source).await?;
                 client
                     .scene_items()
-                    .remove(SceneId::Name(target_scene), item_id)
+                    .remove(SceneId::Name(&item.container), item.item_id)
                     .await
                     .map_err(|e| ObsCmdError::ConnectionError(e))?;
 
                 println!(
                     "Removed scene item '{}' from {}",
                     source,
-                    if target_scene == scene {
-                        format!("scene '{}'", scene)
-                    } else {
-                        format!("group '{}' in scene '{}'", target_scene, scene)
-                    }
+                    item.location(scene)
                 );
             }
             SceneItem::Duplicate { scene, source } => {
-                // Check if source path contains a group separator "/"
-                let target_scene = if let Some(slash_pos) = source.find('/') {
-                    &source[..slash_pos]
-                } else {
-                    scene
-                };
-
-                let item_id = get_scene_item_id(client, target_scene, source).await?;
+                let item = resolve_item(client, scene, source).await?;
                 let new_item_id = client
                     .scene_items()
                     .duplicate(Duplicate {
-                        scene: SceneId::Name(target_scene),
-                        item_id,
+                        scene: SceneId::Name(&item.container),
+                        item_id: item.item_id,
                         destination: None, // Duplicate to same scene/group
                     })
                     .await
@@ -101,81 +192,114 @@ impl CommandHandler for SceneItemHandler {
                 println!(
                     "Duplicated scene item '{}' in {} with new ID: {}",
                     source,
-                    if target_scene == scene {
-                        format!("scene '{}'", scene)
-                    } else {
-                        format!("group '{}' in scene '{}'", target_scene, scene)
-                    },
+                    item.location(scene),
                     new_item_id
                 );
             }
-            SceneItem::Enable { scene, source } => {
-                set_scene_item_enabled(client, scene, source, true).await?;
-                println!("Enabled scene item '{}' in scene '{}'", source, scene);
+            SceneItem::Copy {
+                from_scene,
+                source,
+                to_scene,
+                keep_transform,
+            } => {
+                let item = resolve_item(client, from_scene, source).await?;
+                let new_item_id = client
+                    .scene_items()
+                    .duplicate(Duplicate {
+                        scene: SceneId::Name(&item.container),
+                        item_id: item.item_id,
+                        destination: Some(SceneId::Name(to_scene).into()),
+                    })
+                    .await
+                    .map_err(|e| ObsCmdError::ConnectionError(e))?;
+
+                // OBS copies the transform along with the item
+                if !keep_transform {
+                    client
+                        .scene_items()
+                        .set_transform(SetTransform {
+                            scene: SceneId::Name(to_scene),
+                            item_id: new_item_id,
+                            transform: new_item_transform(),
+                        })
+                        .await
+                        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+                }
+
+                println!(
+                    "Copied scene item '{}' from {} to scene '{}' with new ID: {}",
+                    source,
+                    item.location(from_scene),
+                    to_scene,
+                    new_item_id
+                );
             }
-            SceneItem::Disable { scene, source } => {
-                set_scene_item_enabled(client, scene, source, false).await?;
-                println!("Disabled scene item '{}' in scene '{}'", source, scene);
+            SceneItem::Enable { target, .. } => {
+                let (scene, source) = single_item(target);
+                let item = resolve_item(client, scene, source).await?;
+                set_scene_item_enabled(client, &item, true).await?;
+                println!(
+                    "Enabled scene item '{}' in {}",
+                    source,
+                    item.location(scene)
+                );
             }
-            SceneItem::Toggle { scene, source } => {
-                // Check if source path contains a group separator "/"
-                let target_scene = if let Some(slash_pos) = source.find('/') {
-                    &source[..slash_pos]
-                } else {
-                    scene
-                };
-
-                let item_id = get_scene_item_id(client, target_scene, source).await?;
+            SceneItem::Disable(target) => {
+                let (scene, source) = single_item(target);
+                let item = resolve_item(client, scene, source).await?;
+                set_scene_item_enabled(client, &item, false).await?;
+                println!(
+                    "Disabled scene item '{}' in {}",
+                    source,
+                    item.location(scene)
+                );
+            }
+            SceneItem::Toggle(target) => {
+                let (scene, source) = single_item(target);
+                let item = resolve_item(client, scene, source).await?;
                 let current_state = client
                     .scene_items()
-                    .enabled(SceneId::Name(target_scene), item_id)
+                    .enabled(SceneId::Name(&item.container), item.item_id)
                     .await
                     .map_err(|e| ObsCmdError::ConnectionError(e))?;
                 let new_state = !current_state;
 
-                set_scene_item_enabled(client, target_scene, source, new_state).await?;
+                set_scene_item_enabled(client, &item, new_state).await?;
                 println!(
                     "Scene item '{}' in {}: {}",
                     source,
-                    if target_scene == scene {
-                        format!("scene '{}'", scene)
-                    } else {
-                        format!("group '{}' in scene '{}'", target_scene, scene)
-                    },
+                    item.location(scene),
                     if new_state { "enabled" } else { "disabled" }
                 );
             }
-            SceneItem::Lock { scene, source } => {
-                set_scene_item_locked(client, scene, source, true).await?;
-                println!("Locked scene item '{}' in scene '{}'", source, scene);
+            SceneItem::Lock(target) => {
+                let (scene, source) = single_item(target);
+                let item = resolve_item(client, scene, source).await?;
+                set_scene_item_locked(client, &item, true).await?;
+                println!("Locked scene item '{}' in {}", source, item.location(scene));
             }
-            SceneItem::Unlock { scene, source } => {
-                set_scene_item_locked(client, scene, source, false).await?;
-                println!("Unlocked scene item '{}' in scene '{}'", source, scene);
+            SceneItem::Unlock(target) => {
+                let (scene, source) = single_item(target);
+                let item = resolve_item(client, scene, source).await?;
+                set_scene_item_locked(client, &item, false).await?;
+                println!(
+                    "Unlocked scene item '{}' in {}",
+                    source,
+                    item.location(scene)
+                );
             }
             SceneItem::GetTransform { scene, source } => {
-                // Check if source path contains a group separator "/"
-                let target_scene = if let Some(slash_pos) = source.find('/') {
-                    &source[..slash_pos]
-                } else {
-                    scene
-                };
-
-                let item_id = get_scene_item_id(client, target_scene, source).await?;
+                let item = resolve_item(client, scene, source).await?;
                 let transform = client
                     .scene_items()
-                    .transform(SceneId::Name(target_scene), item_id)
+                    .transform(SceneId::Name(&item.container), item.item_id)
                     .await
                     .map_err(|e| ObsCmdError::ConnectionError(e))?;
 
                 println!(
                     "Transform for scene item '{}' in {}:",
                     source,
-                    if target_scene == scene {
-                        format!("scene '{}'", scene)
-                    } else {
-                        format!("group '{}' in scene '{}'", target_scene, scene)
-                    }
+                    item.location(scene)
                 );
                 println!(
                     "  Position: X: {}, Y: {}",
@@ -193,6 +317,18 @@ impl CommandHandler for SceneItemHandler {
                     transform.crop_top,
                     transform.crop_bottom
                 );
+                println!("  Alignment: {}", alignment_name(transform.alignment));
+                if transform.bounds_type == BoundsType::None {
+                    println!("  Bounds: none");
+                } else {
+                    println!(
+                        "  Bounds: {:?}, {}x{}, alignment: {}",
+                        transform.bounds_type,
+                        transform.bounds_width,
+                        transform.bounds_height,
+                        alignment_name(transform.bounds_alignment)
+                    );
+                }
             }
             SceneItem::SetTransform {
                 scene,
@@ -206,41 +342,108 @@ impl CommandHandler for SceneItemHandler {
                 crop_right,
                 crop_top,
                 crop_bottom,
+                alignment,
+                bounds_type,
+                bounds_width,
+                bounds_height,
+                bounds_alignment,
+                anchor,
+                margin,
             } => {
-                // Check if source path contains a group separator "/"
-                let target_scene = if let Some(slash_pos) = source.find('/') {
-                    &source[..slash_pos]
+                let item = resolve_item(client, scene, source).await?;
+                let current = client
+                    .scene_items()
+                    .transform(SceneId::Name(&item.container), item.item_id)
+                    .await
+                    .map_err(|e| ObsCmdError::ConnectionError(e))?;
+
+                // Percentages and anchors are resolved against the base canvas
+                let uses_canvas = anchor.is_some()
+                    || [position_x, position_y, bounds_width, bounds_height]
+                        .iter()
+                        .any(|value| matches!(value, Some(TransformValue::Percent(_))));
+                let canvas = if uses_canvas {
+                    let video = client
+                        .config()
+                        .video_settings()
+                        .await
+                        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+                    Some((video.base_width as f32, video.base_height as f32))
                 } else {
-                    scene
+                    None
                 };
+                let canvas_width = canvas.map(|(width, _)| width);
+                let canvas_height = canvas.map(|(_, height)| height);
 
-                let item_id = get_scene_item_id(client, target_scene, source).await?;
+                let resolve =
+                    |field: &str, value: &Option<TransformValue>, current: f32, canvas| {
+                        value
+                            .map(|value| resolve_transform_value(field, value, current, canvas))
+                            .transpose()
+                    };
+                let mut position_x =
+                    resolve("position-x", position_x, current.position_x, canvas_width)?;
+                let mut position_y =
+                    resolve("position-y", position_y, current.position_y, canvas_height)?;
+                let scale_x = resolve("scale-x", scale_x, current.scale_x, None)?;
+                let scale_y = resolve("scale-y", scale_y, current.scale_y, None)?;
+                let rotation = resolve("rotation", rotation, current.rotation, None)?;
+                let bounds_width = resolve(
+                    "bounds-width",
+                    bounds_width,
+                    current.bounds_width,
+                    canvas_width,
+                )?;
+                let bounds_height = resolve(
+                    "bounds-height",
+                    bounds_height,
+                    current.bounds_height,
+                    canvas_height,
+                )?;
 
-                // Get current transform to use as base
-                let _current_transform = client
-                    .scene_items()
-                    .transform(SceneId::Name(target_scene), item_id)
-                    .await
-                    .map_err(|e| ObsCmdError::ConnectionError(e))?;
+                if let (Some(anchor), Some((width, height))) = (anchor, canvas) {
+                    // Anchor the item as it will be after this update
+                    let mut updated = current.clone();
+                    updated.scale_x = scale_x.unwrap_or(updated.scale_x);
+                    updated.scale_y = scale_y.unwrap_or(updated.scale_y);
+                    updated.crop_left = crop_left.unwrap_or(updated.crop_left);
+                    updated.crop_right = crop_right.unwrap_or(updated.crop_right);
+                    updated.crop_top = crop_top.unwrap_or(updated.crop_top);
+                    updated.crop_bottom = crop_bottom.unwrap_or(updated.crop_bottom);
+                    updated.alignment = alignment.map_or(updated.alignment, Alignment::from);
+                    updated.bounds_type = bounds_type.map_or(updated.bounds_type, BoundsType::from);
+                    updated.bounds_width = bounds_width.unwrap_or(updated.bounds_width);
+                    updated.bounds_height = bounds_height.unwrap_or(updated.bounds_height);
+
+                    let (x, y) = anchored_position(
+                        &updated,
+                        Alignment::from(*anchor),
+                        *margin as f32,
+                        width,
+                        height,
+                    );
+                    position_x = Some(x);
+                    position_y = Some(y);
+                }
 
                 let transform = SceneItemTransform {
                     position: if position_x.is_some() || position_y.is_some() {
                         Some(Position {
-                            x: position_x.map(|x| x as f32),
-                            y: position_y.map(|y| y as f32),
+                            x: position_x,
+                            y: position_y,
                         })
                     } else {
                         None
                     },
                     scale: if scale_x.is_some() || scale_y.is_some() {
                         Some(Scale {
-                            x: scale_x.map(|x| x as f32),
-                            y: scale_y.map(|y| y as f32),
+                            x: scale_x,
+                            y: scale_y,
                         })
                     } else {
                         None
                     },
-                    rotation: rotation.map(|r| r as f32),
+                    rotation,
                     crop: if crop_left.is_some()
                         || crop_right.is_some()
                         || crop_top.is_some()
@@ -255,15 +458,28 @@ impl CommandHandler for SceneItemHandler {
                     } else {
                         None
                     },
-                    alignment: None, // Keep current alignment
-                    bounds: None,    // Keep current bounds
+                    alignment: alignment.map(Alignment::from),
+                    bounds: if bounds_type.is_some()
+                        || bounds_width.is_some()
+                        || bounds_height.is_some()
+                        || bounds_alignment.is_some()
+                    {
+                        Some(Bounds {
+                            r#type: bounds_type.map(BoundsType::from),
+                            alignment: bounds_alignment.map(Alignment::from),
+                            width: bounds_width,
+                            height: bounds_height,
+                        })
+                    } else {
+                        None
+                    },
                 };
 
                 client
                     .scene_items()
                     .set_transform(SetTransform {
-                        scene: SceneId::Name(target_scene),
-                        item_id,
+                        scene: SceneId::Name(&item.container),
+                        item_id: item.item_id,
                         transform,
                     })
                     .await
@@ -272,36 +488,80 @@ impl CommandHandler for SceneItemHandler {
                 println!(
                     "Updated transform for scene item '{}' in {}",
                     source,
-                    if target_scene == scene {
-                        format!("scene '{}'", scene)
-                    } else {
-                        format!("group '{}' in scene '{}'", target_scene, scene)
-                    }
+                    item.location(scene)
                 );
             }
-            SceneItem::GetIndex { scene, source } => {
-                // Check if source path contains a group separator "/"
-                let target_scene = if let Some(slash_pos) = source.find('/') {
-                    &source[..slash_pos]
-                } else {
-                    scene
-                };
+            SceneItem::Fit { scene, source } => {
+                let item = resolve_item(client, scene, source).await?;
+                let (width, height) = fill_canvas(client, &item, BoundsType::ScaleInner).await?;
+                println!(
+                    "Fitted scene item '{}' in {} to the {}x{} canvas",
+                    source,
+                    item.location(scene),
+                    width,
+                    height
+                );
+            }
+            SceneItem::Stretch { scene, source } => {
+                let item = resolve_item(client, scene, source).await?;
+                let (width, height) = fill_canvas(client, &item, BoundsType::Stretch).await?;
+                println!(
+                    "Stretched scene item '{}' in {} to the {}x{} canvas",
+                    source,
+                    item.location(scene),
+                    width,
+                    height
+                );
+            }
+            SceneItem::Center { scene, source } => {
+                let item = resolve_item(client, scene, source).await?;
+                let video = client
+                    .config()
+                    .video_settings()
+                    .await
+                    .map_err(|e| ObsCmdError::ConnectionError(e))?;
+                let current = client
+                    .scene_items()
+                    .transform(SceneId::Name(&item.container), item.item_id)
+                    .await
+                    .map_err(|e| ObsCmdError::ConnectionError(e))?;
+                let (x, y) = centered_position(&current, video.base_width, video.base_height);
 
-                let item_id = get_scene_item_id(client, target_scene, source).await?;
+                client
+                    .scene_items()
+                    .set_transform(SetTransform {
+                        scene: SceneId::Name(&item.container),
+                        item_id: item.item_id,
+                        transform: SceneItemTransform {
+                            position: Some(Position {
+                                x: Some(x),
+                                y: Some(y),
+                            }),
+                            ..Default::default()
+                        },
+                    })
+                    .await
+                    .map_err(|e| ObsCmdError::ConnectionError(e))?;
+                println!(
+                    "Centered scene item '{}' in {} at X: {}, Y: {}",
+                    source,
+                    item.location(scene),
+                    x,
+                    y
+                );
+            }
+            SceneItem::GetIndex { scene, source } => {
+                let item = resolve_item(client, scene, source).await?;
                 let index = client
                     .scene_items()
-                    .index(SceneId::Name(target_scene), item_id)
+                    .index(SceneId::Name(&item.container), item.item_id)
                     .await
                     .map_err(|e| ObsCmdError::ConnectionError(e))?;
 
                 println!(
                     "Scene item '{}' in {} has index: {}",
                     source,
-                    if target_scene == scene {
-                        format!("scene '{}'", scene)
-                    } else {
-                        format!("group '{}' in scene '{}'", target_scene, scene)
-                    },
+                    item.location(scene),
                     index
                 );
             }
@@ -310,19 +570,12 @@ impl CommandHandler for SceneItemHandler {
                 source,
                 index,
             } => {
-                // Check if source path contains a group separator "/"
-                let target_scene = if let Some(slash_pos) = source.find('/') {
-                    &source[..slash_pos]
-                } else {
-                    scene
-                };
-
-                let item_id = get_scene_item_id(client, target_scene, source).await?;
+                let item = resolve_item(client, scene, source).await?;
                 client
                     .scene_items()
                     .set_index(SetIndex {
-                        scene: SceneId::Name(target_scene),
-                        item_id,
+                        scene: SceneId::Name(&item.container),
+                        item_id: item.item_id,
                         index: *index,
                     })
                     .await
@@ -331,60 +584,35 @@ impl CommandHandler for SceneItemHandler {
                 println!(
                     "Set scene item '{}' in {} to index: {}",
                     source,
-                    if target_scene == scene {
-                        format!("scene '{}'", scene)
-                    } else {
-                        format!("group '{}' in scene '{}'", target_scene, scene)
-                    },
+                    item.location(scene),
                     index
                 );
             }
             SceneItem::GetBlendMode { scene, source } => {
-                // Check if source path contains a group separator "/"
-                let target_scene = if let Some(slash_pos) = source.find('/') {
-                    &source[..slash_pos]
-                } else {
-                    scene
-                };
-
-                let item_id = get_scene_item_id(client, target_scene, source).await?;
+                let item = resolve_item(client, scene, source).await?;
                 let blend_mode = client
                     .scene_items()
-                    .blend_mode(SceneId::Name(target_scene), item_id)
+                    .blend_mode(SceneId::Name(&item.container), item.item_id)
                     .await
                     .map_err(|e| ObsCmdError::ConnectionError(e))?;
 
                 println!(
                     "Scene item '{}' in {} has blend mode: {:?}",
                     source,
-                    if target_scene == scene {
-                        format!("scene '{}'", scene)
-                    } else {
-                        format!("group '{}' in scene '{}'", target_scene, scene)
-                    },
+                    item.location(scene),
                     blend_mode
                 );
             }
-            SceneItem::SetBlendMode {
-                scene,
-                source,
-                blend_mode,
-            } => {
-                // Check if source path contains a group separator "/"
-                let target_scene = if let Some(slash_pos) = source.find('/') {
-                    &source[..slash_pos]
-                } else {
-                    scene
-                };
-
-                let item_id = get_scene_item_id(client, target_scene, source).await?;
+            SceneItem::SetBlendMode { target, blend_mode } => {
+                let (scene, source) = single_item(target);
+                let item = resolve_item(client, scene, source).await?;
                 let parsed_blend_mode = parse_blend_mode(blend_mode)?;
 
                 client
                     .scene_items()
                     .set_blend_mode(SetBlendMode {
-                        scene: SceneId::Name(target_scene),
-                        item_id,
+                        scene: SceneId::Name(&item.container),
+                        item_id: item.item_id,
                         mode: parsed_blend_mode,
                     })
                     .await
@@ -393,11 +621,7 @@ impl CommandHandler for SceneItemHandler {
                 println!(
                     "Set scene item '{}' in {} blend mode to: {:?}",
                     source,
-                    if target_scene == scene {
-                        format!("scene '{}'", scene)
-                    } else {
-                        format!("group '{}' in scene '{}'", target_scene, scene)
-                    },
+                    item.location(scene),
                     parsed_blend_mode
                 );
             }
@@ -412,6 +636,7 @@ impl CommandHandler for SceneItemHandler {
             SceneItem::Create { .. } => "Create a new scene item",
             SceneItem::Remove { .. } => "Remove a scene item",
             SceneItem::Duplicate { .. } => "Duplicate a scene item",
+            SceneItem::Copy { .. } => "Copy a scene item to another scene",
             SceneItem::Enable { .. } => "Enable a scene item",
             SceneItem::Disable { .. } => "Disable a scene item",
             SceneItem::Toggle { .. } => "Toggle a scene item",
@@ -419,6 +644,17 @@ impl CommandHandler for SceneItemHandler {
             SceneItem::Unlock { .. } => "Unlock a scene item",
             SceneItem::GetTransform { .. } => "Get scene item transform",
             SceneItem::SetTransform { .. } => "Set scene item transform",
+            SceneItem::Fit { .. } => "Fit scene item to canvas",
+            SceneItem::Stretch { .. } => "Stretch scene item to canvas",
+            SceneItem::Center { .. } => "Center scene item on canvas",
+            SceneItem::Animate(_) => "Animate scene item transforms",
+            SceneItem::Layout { layout, .. } => match layout {
+                Layout::Grid { .. } => "Arrange scene items in a grid",
+                Layout::Pip { .. } => "Arrange scene items as picture-in-picture",
+                Layout::SideBySide { .. } => "Arrange scene items side by side",
+                Layout::Save { .. } => "Save scene item layout",
+                Layout::Apply { .. } => "Apply saved scene item layout",
+            },
             SceneItem::GetIndex { .. } => "Get scene item index",
             SceneItem::SetIndex { .. } => "Set scene item index",
             SceneItem::GetBlendMode { .. } => "Get scene item blend mode",
@@ -427,111 +663,1341 @@ impl CommandHandler for SceneItemHandler {
     }
 }
 
-/// Helper function to get scene item ID by scene and source name
-/// Supports both regular scenes and groups (for nested sources)
-/// For nested sources in groups, source can be specified as "Group/SourceName"
-async fn get_scene_item_id(client: &Client, scene: &str, source: &str) -> Result<i64> {
-    // Check if source path contains a group separator "/"
-    if let Some(slash_pos) = source.find('/') {
-        // Split into group name and source name
-        let group_name = &source[..slash_pos];
-        let nested_source_name = &source[slash_pos + 1..];
+impl SceneItemHandler {
+    /// Returns the selector and change to apply when the command selects
+    /// items with --match instead of a single path
+    fn bulk_change(&self) -> Result<Option<(ItemSelector, ItemChange, bool)>> {
+        let (target, change, exclusive) = match &self.action {
+            SceneItem::Enable { target, exclusive } => {
+                (target.clone(), ItemChange::Enable, *exclusive)
+            }
+            SceneItem::Disable(target) => (target.clone(), ItemChange::Disable, false),
+            SceneItem::Toggle(target) => (target.clone(), ItemChange::Toggle, false),
+            SceneItem::Lock(target) => (target.clone(), ItemChange::Lock, false),
+            SceneItem::Unlock(target) => (target.clone(), ItemChange::Unlock, false),
+            SceneItem::SetBlendMode { target, blend_mode } => (
+                target.clone(),
+                ItemChange::BlendMode(parse_blend_mode(blend_mode)?),
+                false,
+            ),
+            _ => return Ok(None),
+        };
 
-        // Search within the group
-        client
-            .scene_items()
-            .id(IdItem {
-                scene: SceneId::Name(group_name),
-                source: nested_source_name,
-                search_offset: Some(0),
+        Ok(target
+            .pattern
+            .is_some()
+            .then_some((target, change, exclusive)))
+    }
+}
+
+/// Scene and item path of a selector addressing a single item
+fn single_item(target: &ItemSelector) -> (&str, &str) {
+    // clap requires both unless --match is used
+    (
+        target.scene.as_deref().unwrap_or_default(),
+        target.source.as_deref().unwrap_or_default(),
+    )
+}
+
+/// The change requested for the selected scene items
+#[derive(Clone, Copy)]
+enum ItemChange {
+    Enable,
+    Disable,
+    Toggle,
+    Lock,
+    Unlock,
+    BlendMode(BlendMode),
+}
+
+/// A scene item found while walking a scene's item tree
+struct FlatItem {
+    item: ResolvedItem,
+    /// Item path relative to the scene, usable with every scene-item command
+    path: String,
+    source_name: String,
+    is_group: bool,
+}
+
+fn flatten_item_tree(
+    nodes: Vec<ItemNode>,
+    container: &str,
+    in_group: bool,
+    prefix: &str,
+    out: &mut Vec<FlatItem>,
+) {
+    // Items are listed bottom first, occurrences count from the top
+    let occurrences: Vec<usize> = (0..nodes.len())
+        .map(|i| {
+            1 + nodes[i + 1..]
+                .iter()
+                .filter(|above| above.source_name == nodes[i].source_name)
+                .count()
+        })
+        .collect();
+    for (node, occurrence) in nodes.into_iter().zip(occurrences) {
+        let path = format!("{}{}", prefix, path_segment(&node.source_name, occurrence));
+        let child_prefix = format!("{}/", path);
+        out.push(FlatItem {
+            item: ResolvedItem {
+                container: container.to_string(),
+                in_group,
+                item_id: node.id,
+            },
+            path,
+            source_name: node.source_name.clone(),
+            is_group: node.is_group,
+        });
+        flatten_item_tree(node.children, &node.source_name, true, &child_prefix, out);
+    }
+}
+
+/// Writes a source name as a path segment that [`parse_item_path`] reads
+/// back: characters with a meaning in paths are escaped, and repeated
+/// sources get their `@N` suffix
+fn path_segment(source_name: &str, occurrence: usize) -> String {
+    let mut segment = String::with_capacity(source_name.len());
+    for (i, c) in source_name.chars().enumerate() {
+        if matches!(c, '\\' | '/' | '"' | '@') || (i == 0 && c == '#') {
+            segment.push('\\');
+        }
+        segment.push(c);
+    }
+    if occurrence > 1 {
+        segment.push_str(&format!("@{}", occurrence));
+    }
+    segment
+}
+
+/// Applies a change to every item whose source name matches the selector's
+/// pattern, in one scene or all of them, and prints a per-scene report
+async fn change_matching_items(
+    client: &Client,
+    target: &ItemSelector,
+    change: ItemChange,
+    exclusive: bool,
+) -> Result<()> {
+    let pattern = target.pattern.as_deref().unwrap_or("*");
+    let regex = if target.regex {
+        Some(Regex::new(pattern)?)
+    } else {
+        None
+    };
+    let is_match = |name: &str| match &regex {
+        Some(regex) => regex.is_match(name),
+        None => glob_match(pattern, name),
+    };
+
+    let scenes = match (&target.scene, target.all_scenes) {
+        (Some(_), true) => {
+            return Err(ObsCmdError::InvalidItemSelector {
+                message: "--all-scenes cannot be combined with a scene name".to_string(),
             })
+        }
+        (Some(scene), false) => vec![scene.clone()],
+        (None, _) => client
+            .scenes()
+            .list()
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?
+            .scenes
+            .into_iter()
+            .map(|scene| scene.id.name)
+            .collect(),
+    };
+
+    let options = TreeOptions {
+        recursive: true,
+        long: false,
+        expand_nested: false,
+    };
+    let mut changed_items = 0;
+    let mut matched_scenes = 0;
+
+    for scene in &scenes {
+        let tree =
+            fetch_item_tree(client, scene.clone(), false, options, vec![scene.clone()]).await?;
+        let mut items = Vec::new();
+        flatten_item_tree(tree, scene, false, "", &mut items);
+
+        let matched: Vec<&str> = items
+            .iter()
+            .filter(|item| is_match(&item.source_name))
+            .map(|item| item.path.as_str())
+            .collect();
+        if matched.is_empty() {
+            continue;
+        }
+
+        matched_scenes += 1;
+        println!("Scene '{}':", scene);
+        for item in &items {
+            let is_within = |outer: &str, inner: &str| inner.starts_with(&format!("{}/", outer));
+            let item_change = if matched.contains(&item.path.as_str()) {
+                change
+            } else if exclusive
+                // Keep groups holding a match, and the contents of matched groups, visible
+                && !(item.is_group && matched.iter().any(|m| is_within(&item.path, m)))
+                && !matched.iter().any(|m| is_within(m, &item.path))
+            {
+                ItemChange::Disable
+            } else {
+                continue;
+            };
+
+            let (before, after) = apply_item_change(client, &item.item, item_change).await?;
+            changed_items += 1;
+            println!("  - {}: {} -> {}", item.path, before, after);
+        }
+    }
+
+    println!(
+        "Updated {} item(s) in {} scene(s)",
+        changed_items, matched_scenes
+    );
+    Ok(())
+}
+
+/// Applies one change to a scene item, returning its state before and after
+async fn apply_item_change(
+    client: &Client,
+    item: &ResolvedItem,
+    change: ItemChange,
+) -> Result<(String, String)> {
+    let scene = SceneId::Name(&item.container);
+    let enabled_text = |enabled: bool| if enabled { "enabled" } else { "disabled" }.to_string();
+    let locked_text = |locked: bool| if locked { "locked" } else { "unlocked" }.to_string();
+
+    match change {
+        ItemChange::Enable | ItemChange::Disable | ItemChange::Toggle => {
+            let current = client
+                .scene_items()
+                .enabled(scene, item.item_id)
+                .await
+                .map_err(|e| ObsCmdError::ConnectionError(e))?;
+            let enabled = match change {
+                ItemChange::Enable => true,
+                ItemChange::Disable => false,
+                _ => !current,
+            };
+            set_scene_item_enabled(client, item, enabled).await?;
+            Ok((enabled_text(current), enabled_text(enabled)))
+        }
+        ItemChange::Lock | ItemChange::Unlock => {
+            let current = client
+                .scene_items()
+                .locked(scene, item.item_id)
+                .await
+                .map_err(|e| ObsCmdError::ConnectionError(e))?;
+            let locked = matches!(change, ItemChange::Lock);
+            set_scene_item_locked(client, item, locked).await?;
+            Ok((locked_text(current), locked_text(locked)))
+        }
+        ItemChange::BlendMode(mode) => {
+            let current = client
+                .scene_items()
+                .blend_mode(scene, item.item_id)
+                .await
+                .map_err(|e| ObsCmdError::ConnectionError(e))?;
+            client
+                .scene_items()
+                .set_blend_mode(SetBlendMode {
+                    scene,
+                    item_id: item.item_id,
+                    mode,
+                })
+                .await
+                .map_err(|e| ObsCmdError::ConnectionError(e))?;
+            Ok((format!("{:?}", current), format!("{:?}", mode)))
+        }
+    }
+}
+
+/// What [`fetch_item_tree`] descends into and how much it fetches per item
+#[derive(Clone, Copy)]
+pub(crate) struct TreeOptions {
+    pub recursive: bool,
+    pub long: bool,
+    pub expand_nested: bool,
+}
+
+/// A scene item, with the items of its group or nested scene when expanded
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ItemNode {
+    pub id: i64,
+    pub index: u32,
+    pub source_name: String,
+    pub source_kind: String,
+    pub is_group: bool,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub details: Option<ItemDetails>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<ItemNode>,
+}
+
+/// Per-item state fetched for long listings
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ItemDetails {
+    pub source_uuid: String,
+    pub enabled: bool,
+    pub locked: bool,
+    pub blend_mode: BlendMode,
+    pub transform: ItemTransform,
+}
+
+/// Fetches the items of a scene, or of a group when `in_group` is set, as a
+/// tree. `path` holds the scenes being expanded and guards against cycles.
+pub(crate) fn fetch_item_tree(
+    client: &Client,
+    scene: String,
+    in_group: bool,
+    options: TreeOptions,
+    path: Vec<String>,
+) -> BoxFuture<'_, Result<Vec<ItemNode>>> {
+    async move {
+        let items = if in_group {
+            client.scene_items().list_group(SceneId::Name(&scene)).await
+        } else {
+            client.scene_items().list(SceneId::Name(&scene)).await
+        }
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+
+        let mut nodes = Vec::with_capacity(items.len());
+        for item in items {
+            let is_group = item.is_group.unwrap_or(false);
+            let source_kind = match (item.source_type, &item.input_kind) {
+                (_, Some(kind)) => kind.clone(),
+                (SourceType::Scene, None) if is_group => "group".to_string(),
+                (SourceType::Scene, None) => "scene".to_string(),
+                (source_type, None) => format!("{:?}", source_type).to_lowercase(),
+            };
+
+            let details = if options.long {
+                Some(fetch_item_details(client, &scene, item.id).await?)
+            } else {
+                None
+            };
+
+            let expand = if is_group {
+                options.recursive
+            } else {
+                options.expand_nested && item.source_type == SourceType::Scene
+            };
+            let children = if expand && !path.contains(&item.source_name) {
+                let mut child_path = path.clone();
+                child_path.push(item.source_name.clone());
+                fetch_item_tree(
+                    client,
+                    item.source_name.clone(),
+                    is_group,
+                    options,
+                    child_path,
+                )
+                .await?
+            } else {
+                Vec::new()
+            };
+
+            nodes.push(ItemNode {
+                id: item.id,
+                index: item.index,
+                source_name: item.source_name,
+                source_kind,
+                is_group,
+                details,
+                children,
+            });
+        }
+        Ok(nodes)
+    }
+    .boxed()
+}
+
+async fn fetch_item_details(client: &Client, scene: &str, item_id: i64) -> Result<ItemDetails> {
+    let items = client.scene_items();
+    let source = items
+        .source(ItemSource {
+            scene: SceneId::Name(scene),
+            item_id,
+        })
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+
+    Ok(ItemDetails {
+        source_uuid: source.uuid.to_string(),
+        enabled: items
+            .enabled(SceneId::Name(scene), item_id)
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?,
+        locked: items
+            .locked(SceneId::Name(scene), item_id)
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?,
+        blend_mode: items
+            .blend_mode(SceneId::Name(scene), item_id)
             .await
-            .map_err(|e| ObsCmdError::ConnectionError(e))
+            .map_err(|e| ObsCmdError::ConnectionError(e))?,
+        transform: items
+            .transform(SceneId::Name(scene), item_id)
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?,
+    })
+}
+
+/// Prints an item tree with two spaces of indentation per level
+fn print_item_tree(nodes: &[ItemNode], depth: usize) {
+    let indent = "  ".repeat(depth);
+    for node in nodes {
+        println!(
+            "{}- ID: {}, Source: '{}', Index: {}{}",
+            indent,
+            node.id,
+            node.source_name,
+            node.index,
+            if node.is_group { " [group]" } else { "" }
+        );
+        if let Some(details) = &node.details {
+            let t = &details.transform;
+            println!(
+                "{}    Kind: {}, Enabled: {}, Locked: {}, Blend: {:?}",
+                indent, node.source_kind, details.enabled, details.locked, details.blend_mode
+            );
+            println!(
+                "{}    Transform: pos ({}, {}), scale {}x{}, rotation {}, size {}x{}, crop {}/{}/{}/{}",
+                indent,
+                t.position_x,
+                t.position_y,
+                t.scale_x,
+                t.scale_y,
+                t.rotation,
+                t.width,
+                t.height,
+                t.crop_left,
+                t.crop_right,
+                t.crop_top,
+                t.crop_bottom
+            );
+            println!("{}    UUID: {}", indent, details.source_uuid);
+        }
+        print_item_tree(&node.children, depth + 1);
+    }
+}
+
+/// One step of a scene item path
+#[derive(Debug, PartialEq)]
+enum PathSegment {
+    /// `#12`: the item with this scene item ID
+    Id(i64),
+    /// `Cam` or `Cam@2`: the n-th item (1-based, top first) showing this source
+    Name { name: String, occurrence: usize },
+}
+
+impl std::fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathSegment::Id(id) => write!(f, "#{}", id),
+            PathSegment::Name {
+                name,
+                occurrence: 1,
+            } => write!(f, "{}", name),
+            PathSegment::Name { name, occurrence } => write!(f, "{}@{}", name, occurrence),
+        }
+    }
+}
+
+/// Splits a scene item path such as `Group/Sub/Cam@2` into its segments.
+///
+/// `/` separates groups (or nested scenes) from their items. A backslash
+/// makes the next character literal and double quotes make everything up to
+/// the closing quote literal, so `Intro\/Outro` and `"Intro/Outro"` both name
+/// a source containing a slash. A segment of the form `#12` selects an item
+/// by ID and a trailing `@N` selects the N-th item showing that source.
+fn parse_item_path(path: &str) -> Result<Vec<PathSegment>> {
+    let invalid = |message: &str| ObsCmdError::InvalidItemPath {
+        path: path.to_string(),
+        message: message.to_string(),
+    };
+
+    // Characters of each segment, flagged when escaped or quoted
+    let mut raw_segments: Vec<Vec<(char, bool)>> = vec![Vec::new()];
+    let mut chars = path.chars();
+    let mut quoted = false;
+    while let Some(c) = chars.next() {
+        let segment = raw_segments.last_mut().expect("at least one segment");
+        match c {
+            '\\' => match chars.next() {
+                Some(escaped) => segment.push((escaped, true)),
+                None => return Err(invalid("trailing backslash")),
+            },
+            '"' => quoted = !quoted,
+            '/' if !quoted => raw_segments.push(Vec::new()),
+            c => segment.push((c, quoted)),
+        }
+    }
+    if quoted {
+        return Err(invalid("unterminated quote"));
+    }
+
+    raw_segments
+        .into_iter()
+        .map(|segment| {
+            if segment.is_empty() {
+                return Err(invalid("empty path segment"));
+            }
+            let text: String = segment.iter().map(|(c, _)| c).collect();
+
+            if segment[0] == ('#', false) {
+                return text[1..]
+                    .parse()
+                    .map(PathSegment::Id)
+                    .map_err(|_| invalid("expected a numeric item ID after '#'"));
+            }
+
+            let marker = segment.iter().rposition(|&entry| entry == ('@', false));
+            if let Some(position) = marker.filter(|&p| p > 0) {
+                let suffix = &segment[position + 1..];
+                if !suffix.is_empty() && suffix.iter().all(|(c, _)| c.is_ascii_digit()) {
+                    let occurrence: usize = suffix
+                        .iter()
+                        .map(|(c, _)| c)
+                        .collect::<String>()
+                        .parse()
+                        .map_err(|_| invalid("occurrence is too large"))?;
+                    if occurrence == 0 {
+                        return Err(invalid("occurrences are counted from 1"));
+                    }
+                    return Ok(PathSegment::Name {
+                        name: segment[..position].iter().map(|(c, _)| c).collect(),
+                        occurrence,
+                    });
+                }
+            }
+
+            Ok(PathSegment::Name {
+                name: text,
+                occurrence: 1,
+            })
+        })
+        .collect()
+}
+
+/// A scene item located by [`resolve_item`]
+pub(crate) struct ResolvedItem {
+    /// Name of the scene or group that directly contains the item
+    pub container: String,
+    /// Whether the container is a group rather than a (nested) scene
+    pub in_group: bool,
+    pub item_id: i64,
+}
+
+impl ResolvedItem {
+    /// Describes where the item lives relative to the scene it was looked up in
+    pub fn location(&self, scene: &str) -> String {
+        if self.container == scene {
+            format!("scene '{}'", scene)
+        } else if self.in_group {
+            format!("group '{}' in scene '{}'", self.container, scene)
+        } else {
+            format!("nested scene '{}' in scene '{}'", self.container, scene)
+        }
+    }
+}
+
+/// Resolves a scene item path (see [`parse_item_path`]) starting at `scene`,
+/// descending through groups and nested scenes one segment at a time
+pub(crate) async fn resolve_item(client: &Client, scene: &str, path: &str) -> Result<ResolvedItem> {
+    let segments = parse_item_path(path)?;
+    let mut container = scene.to_string();
+    let mut in_group = false;
+
+    for (position, segment) in segments.iter().enumerate() {
+        let items = if in_group {
+            client
+                .scene_items()
+                .list_group(SceneId::Name(&container))
+                .await
+        } else {
+            client.scene_items().list(SceneId::Name(&container)).await
+        }
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+
+        let item = match segment {
+            PathSegment::Id(id) => items.into_iter().find(|item| item.id == *id),
+            // Items are listed bottom first, occurrences count from the top
+            PathSegment::Name { name, occurrence } => items
+                .into_iter()
+                .rev()
+                .filter(|item| &item.source_name == name)
+                .nth(occurrence - 1),
+        }
+        .ok_or_else(|| ObsCmdError::SceneItemNotFound {
+            item: segment.to_string(),
+            container: container.clone(),
+        })?;
+
+        if position + 1 == segments.len() {
+            return Ok(ResolvedItem {
+                container,
+                in_group,
+                item_id: item.id,
+            });
+        }
+
+        in_group = item.is_group.unwrap_or(false);
+        if !in_group && item.source_type != SourceType::Scene {
+            return Err(ObsCmdError::NotAnItemContainer {
+                item: item.source_name,
+            });
+        }
+        container = item.source_name;
+    }
+
+    Err(ObsCmdError::InvalidItemPath {
+        path: path.to_string(),
+        message: "empty path".to_string(),
+    })
+}
+
+/// Helper function to set scene item enabled state
+async fn set_scene_item_enabled(client: &Client, item: &ResolvedItem, enabled: bool) -> Result<()> {
+    client
+        .scene_items()
+        .set_enabled(SetEnabledItem {
+            scene: SceneId::Name(&item.container),
+            item_id: item.item_id,
+            enabled,
+        })
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))
+}
+
+/// Helper function to set scene item locked state
+async fn set_scene_item_locked(client: &Client, item: &ResolvedItem, locked: bool) -> Result<()> {
+    client
+        .scene_items()
+        .set_locked(SetLocked {
+            scene: SceneId::Name(&item.container),
+            item_id: item.item_id,
+            locked,
+        })
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))
+}
+
+impl From<ItemAlignment> for Alignment {
+    fn from(alignment: ItemAlignment) -> Self {
+        match alignment {
+            ItemAlignment::TopLeft => Alignment::TOP | Alignment::LEFT,
+            ItemAlignment::Top => Alignment::TOP,
+            ItemAlignment::TopRight => Alignment::TOP | Alignment::RIGHT,
+            ItemAlignment::Left => Alignment::LEFT,
+            ItemAlignment::Center => Alignment::CENTER,
+            ItemAlignment::Right => Alignment::RIGHT,
+            ItemAlignment::BottomLeft => Alignment::BOTTOM | Alignment::LEFT,
+            ItemAlignment::Bottom => Alignment::BOTTOM,
+            ItemAlignment::BottomRight => Alignment::BOTTOM | Alignment::RIGHT,
+        }
+    }
+}
+
+impl From<ItemBoundsType> for BoundsType {
+    fn from(bounds_type: ItemBoundsType) -> Self {
+        match bounds_type {
+            ItemBoundsType::None => BoundsType::None,
+            ItemBoundsType::Stretch => BoundsType::Stretch,
+            ItemBoundsType::ScaleInner => BoundsType::ScaleInner,
+            ItemBoundsType::ScaleOuter => BoundsType::ScaleOuter,
+            ItemBoundsType::ScaleToWidth => BoundsType::ScaleToWidth,
+            ItemBoundsType::ScaleToHeight => BoundsType::ScaleToHeight,
+            ItemBoundsType::MaxOnly => BoundsType::MaxOnly,
+        }
+    }
+}
+
+impl From<Alignment> for ItemAlignment {
+    fn from(alignment: Alignment) -> Self {
+        let top = alignment.contains(Alignment::TOP);
+        let bottom = alignment.contains(Alignment::BOTTOM);
+        let left = alignment.contains(Alignment::LEFT);
+        let right = alignment.contains(Alignment::RIGHT);
+        match (top, bottom, left, right) {
+            (true, _, true, _) => ItemAlignment::TopLeft,
+            (true, _, _, true) => ItemAlignment::TopRight,
+            (true, _, _, _) => ItemAlignment::Top,
+            (_, true, true, _) => ItemAlignment::BottomLeft,
+            (_, true, _, true) => ItemAlignment::BottomRight,
+            (_, true, _, _) => ItemAlignment::Bottom,
+            (_, _, true, _) => ItemAlignment::Left,
+            (_, _, _, true) => ItemAlignment::Right,
+            _ => ItemAlignment::Center,
+        }
+    }
+}
+
+impl From<BoundsType> for ItemBoundsType {
+    fn from(bounds_type: BoundsType) -> Self {
+        match bounds_type {
+            BoundsType::Stretch => ItemBoundsType::Stretch,
+            BoundsType::ScaleInner => ItemBoundsType::ScaleInner,
+            BoundsType::ScaleOuter => ItemBoundsType::ScaleOuter,
+            BoundsType::ScaleToWidth => ItemBoundsType::ScaleToWidth,
+            BoundsType::ScaleToHeight => ItemBoundsType::ScaleToHeight,
+            BoundsType::MaxOnly => ItemBoundsType::MaxOnly,
+            _ => ItemBoundsType::None,
+        }
+    }
+}
+
+/// Names an alignment the way `--alignment` accepts it
+fn alignment_name(alignment: Alignment) -> &'static str {
+    let vertical = if alignment.contains(Alignment::TOP) {
+        "top"
+    } else if alignment.contains(Alignment::BOTTOM) {
+        "bottom"
     } else {
-        // Direct search in the scene
-        client
+        ""
+    };
+    let horizontal = if alignment.contains(Alignment::LEFT) {
+        "left"
+    } else if alignment.contains(Alignment::RIGHT) {
+        "right"
+    } else {
+        ""
+    };
+
+    match (vertical, horizontal) {
+        ("", "") => "center",
+        ("top", "left") => "top-left",
+        ("top", "right") => "top-right",
+        ("bottom", "left") => "bottom-left",
+        ("bottom", "right") => "bottom-right",
+        ("", side) => side,
+        (side, _) => side,
+    }
+}
+
+/// Fraction of the item's width and height at which its alignment point lies
+fn alignment_offset(alignment: Alignment) -> (f32, f32) {
+    let x = if alignment.contains(Alignment::LEFT) {
+        0.0
+    } else if alignment.contains(Alignment::RIGHT) {
+        1.0
+    } else {
+        0.5
+    };
+    let y = if alignment.contains(Alignment::TOP) {
+        0.0
+    } else if alignment.contains(Alignment::BOTTOM) {
+        1.0
+    } else {
+        0.5
+    };
+    (x, y)
+}
+
+/// Size of the item on the canvas: its bounding box if it has one,
+/// otherwise its cropped and scaled source size (rotation is not applied)
+fn item_size(transform: &ItemTransform) -> (f32, f32) {
+    if transform.bounds_type != BoundsType::None {
+        return (transform.bounds_width, transform.bounds_height);
+    }
+    let width = transform.source_width - (transform.crop_left + transform.crop_right) as f32;
+    let height = transform.source_height - (transform.crop_top + transform.crop_bottom) as f32;
+    (width * transform.scale_x, height * transform.scale_y)
+}
+
+/// Position that puts the item's box in the middle of the canvas
+fn centered_position(
+    transform: &ItemTransform,
+    canvas_width: u32,
+    canvas_height: u32,
+) -> (f32, f32) {
+    let (width, height) = item_size(transform);
+    let (offset_x, offset_y) = alignment_offset(transform.alignment);
+    (
+        (canvas_width as f32 - width) / 2.0 + width * offset_x,
+        (canvas_height as f32 - height) / 2.0 + height * offset_y,
+    )
+}
+
+/// Position that puts the item's box against the anchor point of the canvas,
+/// kept `margin` pixels away from the edges it touches
+fn anchored_position(
+    transform: &ItemTransform,
+    anchor: Alignment,
+    margin: f32,
+    canvas_width: f32,
+    canvas_height: f32,
+) -> (f32, f32) {
+    let (width, height) = item_size(transform);
+    let (anchor_x, anchor_y) = alignment_offset(anchor);
+    let (offset_x, offset_y) = alignment_offset(transform.alignment);
+    // Left/top edge of the box: margin from the near edge, centered, or
+    // margin from the far edge
+    let left = anchor_x * (canvas_width - width) + margin * (1.0 - 2.0 * anchor_x);
+    let top = anchor_y * (canvas_height - height) + margin * (1.0 - 2.0 * anchor_y);
+    (left + width * offset_x, top + height * offset_y)
+}
+
+/// Moves scene items towards a target transform in lockstep, one
+/// SetSceneItemTransform request per item per frame
+async fn animate_scene_items(client: &Client, animation: &ItemAnimation) -> Result<()> {
+    let ItemAnimation {
+        scene,
+        items,
+        duration,
+        easing,
+        fps,
+        on_abort,
+        ..
+    } = animation;
+
+    let mut tweens = Vec::with_capacity(items.len());
+    for path in items {
+        let item = resolve_item(client, scene, path).await?;
+        let start = client
             .scene_items()
-            .id(IdItem {
-                scene: SceneId::Name(scene),
-                source,
-                search_offset: Some(0),
-            })
+            .transform(SceneId::Name(&item.container), item.item_id)
             .await
-            .map_err(|e| ObsCmdError::ConnectionError(e))
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+        tweens.push((item, start));
+    }
+
+    println!(
+        "Animating {} scene item(s) in scene '{}' over {:.2}s",
+        tweens.len(),
+        scene,
+        duration.as_secs_f64()
+    );
+
+    let set_frame = |t: f64| {
+        let updates = tweens.iter().map(move |(item, start)| async move {
+            client
+                .scene_items()
+                .set_transform(SetTransform {
+                    scene: SceneId::Name(&item.container),
+                    item_id: item.item_id,
+                    transform: tween_transform(start, animation, t),
+                })
+                .await
+        });
+        async move {
+            try_join_all(updates)
+                .await
+                .map_err(|e| ObsCmdError::ConnectionError(e))
+        }
+    };
+
+    let mut timeline = Timeline::new(*duration, *fps, *easing);
+    let frames = async {
+        while let Some(t) = timeline.next_frame().await {
+            set_frame(t).await?;
+        }
+        Ok::<_, ObsCmdError>(())
+    };
+
+    tokio::select! {
+        result = frames => {
+            result?;
+            println!("Animation completed");
+        }
+        _ = tokio::signal::ctrl_c() => {
+            let (t, snapped) = match on_abort {
+                AbortSnap::Start => (0.0, "start"),
+                AbortSnap::End => (1.0, "target"),
+            };
+            set_frame(t).await?;
+            println!("Animation interrupted, snapped to the {} transform", snapped);
+        }
     }
+    Ok(())
 }
 
-/// Helper function to set scene item enabled state
-async fn set_scene_item_enabled(
+/// Transform of an animated item at eased progress `t`, touching only the
+/// properties the animation has targets for
+fn tween_transform(start: &ItemTransform, animation: &ItemAnimation, t: f64) -> SceneItemTransform {
+    let tween = |from: f32, to: f64| Some(lerp(from as f64, to, t) as f32);
+    let tween_crop = |from: u32, to: u32| Some(lerp(from as f64, to as f64, t).round() as u32);
+
+    SceneItemTransform {
+        position: animation.to_position.map(|(x, y)| Position {
+            x: tween(start.position_x, x),
+            y: tween(start.position_y, y),
+        }),
+        scale: animation.to_scale.map(|(x, y)| Scale {
+            x: tween(start.scale_x, x),
+            y: tween(start.scale_y, y),
+        }),
+        rotation: animation
+            .to_rotation
+            .and_then(|rotation| tween(start.rotation, rotation)),
+        crop: animation.to_crop.map(|[left, top, right, bottom]| Crop {
+            left: tween_crop(start.crop_left, left),
+            top: tween_crop(start.crop_top, top),
+            right: tween_crop(start.crop_right, right),
+            bottom: tween_crop(start.crop_bottom, bottom),
+        }),
+        ..Default::default()
+    }
+}
+
+/// Arranges scene items with a computed layout, or saves or reapplies a
+/// named layout from the config file
+async fn run_layout(client: &Client, scene: &str, layout: &Layout) -> Result<()> {
+    let (canvas_width, canvas_height) = match layout {
+        Layout::Save { name, items } => return save_layout(client, scene, name, items).await,
+        Layout::Apply { name } => return apply_saved_layout(client, scene, name).await,
+        _ => {
+            let video = client
+                .config()
+                .video_settings()
+                .await
+                .map_err(|e| ObsCmdError::ConnectionError(e))?;
+            (video.base_width as f32, video.base_height as f32)
+        }
+    };
+    let canvas = |margin: f64| Rect::canvas(canvas_width, canvas_height, margin as f32);
+
+    match layout {
+        Layout::Grid {
+            items,
+            columns,
+            gap,
+            margin,
+        } => {
+            let cells = grid_cells(canvas(*margin), items.len(), *columns, *gap as f32);
+            let placements = items
+                .iter()
+                .zip(cells)
+                .map(|(item, cell)| (item.as_str(), cell, Alignment::CENTER))
+                .collect::<Vec<_>>();
+            arrange_items(client, scene, &placements).await?;
+            println!(
+                "Arranged {} scene item(s) in scene '{}' in a grid",
+                items.len(),
+                scene
+            );
+        }
+        Layout::SideBySide {
+            left,
+            right,
+            gap,
+            margin,
+        } => {
+            let cells = grid_cells(canvas(*margin), 2, Some(2), *gap as f32);
+            let placements = [
+                (left.as_str(), cells[0], Alignment::CENTER),
+                (right.as_str(), cells[1], Alignment::CENTER),
+            ];
+            arrange_items(client, scene, &placements).await?;
+            println!(
+                "Arranged '{}' and '{}' side by side in scene '{}'",
+                left, right, scene
+            );
+        }
+        Layout::Pip {
+            main,
+            inset,
+            corner,
+            size,
+            margin,
+        } => {
+            let corner = Alignment::from(*corner);
+            let cell = inset_cell(
+                canvas(*margin),
+                canvas_width,
+                canvas_height,
+                *size as f32,
+                alignment_offset(corner),
+            );
+            let placements = [
+                (main.as_str(), canvas(0.0), Alignment::CENTER),
+                (inset.as_str(), cell, corner),
+            ];
+            let items = arrange_items(client, scene, &placements).await?;
+            raise_above(client, &items[1], &items[0]).await?;
+            println!(
+                "Arranged '{}' with '{}' inset in the {} corner of scene '{}'",
+                main,
+                inset,
+                alignment_name(corner),
+                scene
+            );
+        }
+        Layout::Save { .. } | Layout::Apply { .. } => {}
+    }
+    Ok(())
+}
+
+/// Fits each item into its cell, placed inside the cell by the alignment.
+/// All transforms are computed before any of them is sent
+async fn arrange_items(
     client: &Client,
     scene: &str,
-    source: &str,
-    enabled: bool,
-) -> Result<()> {
-    // Check if source path contains a group separator "/"
-    if let Some(slash_pos) = source.find('/') {
-        // Split into group name and source name
-        let group_name = &source[..slash_pos];
-        let nested_source_name = &source[slash_pos + 1..];
+    placements: &[(&str, Rect, Alignment)],
+) -> Result<Vec<ResolvedItem>> {
+    let mut updates = Vec::with_capacity(placements.len());
+    for (path, cell, alignment) in placements {
+        let item = resolve_item(client, scene, path).await?;
+        let current = client
+            .scene_items()
+            .transform(SceneId::Name(&item.container), item.item_id)
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+        let transform = placed_transform(&current, *cell, *alignment);
+        updates.push((item, transform));
+    }
 
-        let item_id = get_scene_item_id(client, group_name, nested_source_name).await?;
+    try_join_all(updates.into_iter().map(|(item, transform)| async move {
         client
             .scene_items()
-            .set_enabled(SetEnabledItem {
-                scene: SceneId::Name(group_name),
-                item_id,
-                enabled,
+            .set_transform(SetTransform {
+                scene: SceneId::Name(&item.container),
+                item_id: item.item_id,
+                transform,
             })
             .await
-            .map_err(|e| ObsCmdError::ConnectionError(e))
-    } else {
-        let item_id = get_scene_item_id(client, scene, source).await?;
+            .map(|_| item)
+    }))
+    .await
+    .map_err(|e| ObsCmdError::ConnectionError(e))
+}
+
+/// Transform that fits the item's cropped source into the cell without
+/// distortion. Sources without a size yet (e.g. an idle capture) get a
+/// bounding box of the cell instead, so OBS fits them once they have one
+fn placed_transform(
+    current: &ItemTransform,
+    cell: Rect,
+    alignment: Alignment,
+) -> SceneItemTransform {
+    let width = current.source_width - (current.crop_left + current.crop_right) as f32;
+    let height = current.source_height - (current.crop_top + current.crop_bottom) as f32;
+
+    let (x, y, scale, bounds) = match fit_in_cell(width, height, cell, alignment_offset(alignment))
+    {
+        Some((x, y, scale)) => (
+            x,
+            y,
+            scale,
+            Bounds {
+                r#type: Some(BoundsType::None),
+                ..Default::default()
+            },
+        ),
+        None => (
+            cell.x,
+            cell.y,
+            1.0,
+            Bounds {
+                r#type: Some(BoundsType::ScaleInner),
+                alignment: Some(alignment),
+                width: Some(cell.width),
+                height: Some(cell.height),
+            },
+        ),
+    };
+
+    SceneItemTransform {
+        position: Some(Position {
+            x: Some(x),
+            y: Some(y),
+        }),
+        rotation: Some(0.0),
+        scale: Some(Scale {
+            x: Some(scale),
+            y: Some(scale),
+        }),
+        alignment: Some(Alignment::TOP | Alignment::LEFT),
+        bounds: Some(bounds),
+        crop: None,
+    }
+}
+
+/// Moves `item` directly above `below` when both share a container and it
+/// is currently underneath
+async fn raise_above(client: &Client, item: &ResolvedItem, below: &ResolvedItem) -> Result<()> {
+    if item.container != below.container {
+        return Ok(());
+    }
+    let scene = SceneId::Name(&item.container);
+    let index = client
+        .scene_items()
+        .index(scene, item.item_id)
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    let below_index = client
+        .scene_items()
+        .index(scene, below.item_id)
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+
+    if index < below_index {
         client
             .scene_items()
-            .set_enabled(SetEnabledItem {
-                scene: SceneId::Name(scene),
-                item_id,
-                enabled,
+            .set_index(SetIndex {
+                scene,
+                item_id: item.item_id,
+                index: below_index,
             })
             .await
-            .map_err(|e| ObsCmdError::ConnectionError(e))
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
     }
+    Ok(())
 }
 
-/// Helper function to set scene item locked state
-async fn set_scene_item_locked(
+/// Stores the current transforms of the items (every top-level item when
+/// none are given) as a named layout in the config file
+async fn save_layout(client: &Client, scene: &str, name: &str, items: &[String]) -> Result<()> {
+    let paths = if items.is_empty() {
+        let options = TreeOptions {
+            recursive: false,
+            long: false,
+            expand_nested: false,
+        };
+        let nodes = fetch_item_tree(
+            client,
+            scene.to_string(),
+            false,
+            options,
+            vec![scene.to_string()],
+        )
+        .await?;
+        let mut flat = Vec::new();
+        flatten_item_tree(nodes, scene, false, "", &mut flat);
+        // Top-most item first, matching the OBS source list
+        flat.into_iter().rev().map(|item| item.path).collect()
+    } else {
+        items.to_vec()
+    };
+
+    let mut entries = Vec::with_capacity(paths.len());
+    for path in paths {
+        let item = resolve_item(client, scene, &path).await?;
+        let transform = client
+            .scene_items()
+            .transform(SceneId::Name(&item.container), item.item_id)
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+        entries.push(LayoutEntry {
+            item: path,
+            transform,
+        });
+    }
+
+    let count = entries.len();
+    let mut config = UserConfig::load()?;
+    config.layouts.insert(name.to_string(), entries);
+    let path = config.save()?;
+
+    println!(
+        "Saved layout '{}' with {} scene item(s) from scene '{}' to {}",
+        name,
+        count,
+        scene,
+        path.display()
+    );
+    Ok(())
+}
+
+/// Restores a saved layout onto the items of a scene with the same paths,
+/// skipping items the scene does not have
+async fn apply_saved_layout(client: &Client, scene: &str, name: &str) -> Result<()> {
+    let config = UserConfig::load()?;
+    let entries = config
+        .layouts
+        .get(name)
+        .ok_or_else(|| ObsCmdError::LayoutNotFound {
+            name: name.to_string(),
+        })?;
+
+    let mut updates = Vec::with_capacity(entries.len());
+    for entry in entries {
+        match resolve_item(client, scene, &entry.item).await {
+            Ok(item) => updates.push((item, transform_request(&entry.transform))),
+            Err(ObsCmdError::SceneItemNotFound { .. }) => {
+                println!("Skipped '{}': not found in scene '{}'", entry.item, scene);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    let count = updates.len();
+    try_join_all(updates.into_iter().map(|(item, transform)| async move {
+        client
+            .scene_items()
+            .set_transform(SetTransform {
+                scene: SceneId::Name(&item.container),
+                item_id: item.item_id,
+                transform,
+            })
+            .await
+    }))
+    .await
+    .map_err(|e| ObsCmdError::ConnectionError(e))?;
+
+    println!(
+        "Applied layout '{}' to {} scene item(s) in scene '{}'",
+        name, count, scene
+    );
+    Ok(())
+}
+
+/// Request that restores every editable property of a transform read from
+/// OBS
+pub(crate) fn transform_request(transform: &ItemTransform) -> SceneItemTransform {
+    TransformState::from(transform).request()
+}
+
+/// Applies a transform value to the current value. Percentages need the
+/// canvas size along the same axis
+fn resolve_transform_value(
+    field: &str,
+    value: TransformValue,
+    current: f32,
+    canvas: Option<f32>,
+) -> Result<f32> {
+    let resolved = match value {
+        TransformValue::Absolute(value) => value as f32,
+        TransformValue::Offset(offset) => current + offset as f32,
+        TransformValue::Factor(factor) => current * factor as f32,
+        TransformValue::Percent(percent) => match canvas {
+            Some(size) => size * percent as f32 / 100.0,
+            None => {
+                return Err(ObsCmdError::InvalidTransformValue {
+                    field: field.to_string(),
+                    message: "canvas percentages only apply to positions and bounds".to_string(),
+                })
+            }
+        },
+    };
+    Ok(resolved)
+}
+
+/// Sizes the item to the whole canvas the way OBS's "Fit to screen" and
+/// "Stretch to screen" do, returning the canvas size
+async fn fill_canvas(
     client: &Client,
-    scene: &str,
-    source: &str,
+    item: &ResolvedItem,
+    bounds_type: BoundsType,
+) -> Result<(u32, u32)> {
+    let video = client
+        .config()
+        .video_settings()
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+
+    client
+        .scene_items()
+        .set_transform(SetTransform {
+            scene: SceneId::Name(&item.container),
+            item_id: item.item_id,
+            transform: SceneItemTransform {
+                position: Some(Position {
+                    x: Some(0.0),
+                    y: Some(0.0),
+                }),
+                rotation: Some(0.0),
+                scale: Some(Scale {
+                    x: Some(1.0),
+                    y: Some(1.0),
+                }),
+                alignment: Some(Alignment::TOP | Alignment::LEFT),
+                bounds: Some(Bounds {
+                    r#type: Some(bounds_type),
+                    alignment: Some(Alignment::CENTER),
+                    width: Some(video.base_width as f32),
+                    height: Some(video.base_height as f32),
+                }),
+                crop: None,
+            },
+        })
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+
+    Ok((video.base_width, video.base_height))
+}
+
+/// Applies the options of `scene-item create` to the new (hidden) item and
+/// then shows it if requested
+async fn setup_new_item(
+    client: &Client,
+    item: &ResolvedItem,
+    transform: Option<SceneItemTransform>,
+    index: Option<u32>,
+    blend_mode: Option<BlendMode>,
     locked: bool,
+    enabled: bool,
 ) -> Result<()> {
-    // Check if source path contains a group separator "/"
-    if let Some(slash_pos) = source.find('/') {
-        // Split into group name and source name
-        let group_name = &source[..slash_pos];
-        let nested_source_name = &source[slash_pos + 1..];
-
-        let item_id = get_scene_item_id(client, group_name, nested_source_name).await?;
+    let scene = SceneId::Name(&item.container);
+    if let Some(transform) = transform {
         client
             .scene_items()
-            .set_locked(SetLocked {
-                scene: SceneId::Name(group_name),
-                item_id,
-                locked,
+            .set_transform(SetTransform {
+                scene,
+                item_id: item.item_id,
+                transform,
             })
             .await
-            .map_err(|e| ObsCmdError::ConnectionError(e))
-    } else {
-        let item_id = get_scene_item_id(client, scene, source).await?;
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    }
+    if let Some(index) = index {
         client
             .scene_items()
-            .set_locked(SetLocked {
-                scene: SceneId::Name(scene),
-                item_id,
-                locked,
+            .set_index(SetIndex {
+                scene,
+                item_id: item.item_id,
+                index,
+            })
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    }
+    if let Some(mode) = blend_mode {
+        client
+            .scene_items()
+            .set_blend_mode(SetBlendMode {
+                scene,
+                item_id: item.item_id,
+                mode,
             })
             .await
-            .map_err(|e| ObsCmdError::ConnectionError(e))
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    }
+    if locked {
+        set_scene_item_locked(client, item, true).await?;
+    }
+    if enabled {
+        set_scene_item_enabled(client, item, true).await?;
+    }
+    Ok(())
+}
+
+/// Transform of a freshly created scene item: top-left of the canvas at
+/// full size, uncropped and without a bounding box
+fn new_item_transform() -> SceneItemTransform {
+    SceneItemTransform {
+        position: Some(Position {
+            x: Some(0.0),
+            y: Some(0.0),
+        }),
+        rotation: Some(0.0),
+        scale: Some(Scale {
+            x: Some(1.0),
+            y: Some(1.0),
+        }),
+        alignment: Some(Alignment::TOP | Alignment::LEFT),
+        bounds: Some(Bounds {
+            r#type: Some(BoundsType::None),
+            ..Default::default()
+        }),
+        crop: Some(Crop {
+            left: Some(0),
+            right: Some(0),
+            top: Some(0),
+            bottom: Some(0),
+        }),
     }
 }
 
@@ -550,3 +2016,291 @@ fn parse_blend_mode(blend_mode: &str) -> Result<BlendMode> {
         }),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        alignment_name, anchored_position, centered_position, flatten_item_tree, parse_item_path,
+        placed_transform, resolve_transform_value, tween_transform, ItemNode, ItemTransform,
+        PathSegment,
+    };
+    use crate::cli::{AbortSnap, Easing, ItemAlignment, ItemAnimation, TransformValue};
+    use crate::layout::Rect;
+    use obws::common::{Alignment, BoundsType};
+    use std::time::Duration;
+
+    fn name(name: &str, occurrence: usize) -> PathSegment {
+        PathSegment::Name {
+            name: name.to_string(),
+            occurrence,
+        }
+    }
+
+    #[test]
+    fn test_parse_item_path() {
+        assert_eq!(parse_item_path("Cam").unwrap(), vec![name("Cam", 1)]);
+        assert_eq!(
+            parse_item_path("Group/Sub/Cam@2").unwrap(),
+            vec![name("Group", 1), name("Sub", 1), name("Cam", 2)]
+        );
+        assert_eq!(
+            parse_item_path("Group/#12").unwrap(),
+            vec![name("Group", 1), PathSegment::Id(12)]
+        );
+        assert_eq!(
+            parse_item_path(r"Intro\/Outro").unwrap(),
+            vec![name("Intro/Outro", 1)]
+        );
+        assert_eq!(
+            parse_item_path(r#""Intro/Outro"@3"#).unwrap(),
+            vec![name("Intro/Outro", 3)]
+        );
+        assert_eq!(
+            parse_item_path(r"\#1 Fan/user\@2").unwrap(),
+            vec![name("#1 Fan", 1), name("user@2", 1)]
+        );
+        assert_eq!(
+            parse_item_path("me@home").unwrap(),
+            vec![name("me@home", 1)]
+        );
+    }
+
+    #[test]
+    fn test_flatten_item_tree_paths() {
+        let node = |id: i64, source: &str, children: Vec<ItemNode>| ItemNode {
+            id,
+            index: 0,
+            source_name: source.to_string(),
+            source_kind: String::new(),
+            is_group: !children.is_empty(),
+            details: None,
+            children,
+        };
+        // Bottom first, as OBS lists them
+        let nodes = vec![
+            node(1, "Cam", vec![]),
+            node(
+                2,
+                "Group",
+                vec![node(3, "Cam", vec![]), node(4, "Cam", vec![])],
+            ),
+            node(5, "Cam", vec![]),
+            node(6, "#1 Fan/user@2", vec![]),
+        ];
+        let mut flat = Vec::new();
+        flatten_item_tree(nodes, "Main", false, "", &mut flat);
+        let paths: Vec<(&str, i64)> = flat
+            .iter()
+            .map(|item| (item.path.as_str(), item.item.item_id))
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                ("Cam@2", 1),
+                ("Group", 2),
+                ("Group/Cam@2", 3),
+                ("Group/Cam", 4),
+                ("Cam", 5),
+                (r"\#1 Fan\/user\@2", 6),
+            ]
+        );
+        assert_eq!(
+            parse_item_path(paths[5].0).unwrap(),
+            vec![name("#1 Fan/user@2", 1)]
+        );
+    }
+
+    #[test]
+    fn test_parse_item_path_errors() {
+        for path in ["", "Group/", "Cam@0", "#abc", r#""open"#, "trailing\\"] {
+            assert!(
+                parse_item_path(path).is_err(),
+                "{:?} should be invalid",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_centered_position() {
+        let transform = ItemTransform {
+            source_width: 1280.0,
+            source_height: 720.0,
+            scale_x: 0.5,
+            scale_y: 0.5,
+            alignment: Alignment::TOP | Alignment::LEFT,
+            ..Default::default()
+        };
+        assert_eq!(centered_position(&transform, 1920, 1080), (640.0, 360.0));
+
+        let transform = ItemTransform {
+            alignment: Alignment::CENTER,
+            ..transform
+        };
+        assert_eq!(centered_position(&transform, 1920, 1080), (960.0, 540.0));
+
+        let transform = ItemTransform {
+            alignment: Alignment::BOTTOM | Alignment::RIGHT,
+            bounds_type: BoundsType::ScaleInner,
+            bounds_width: 400.0,
+            bounds_height: 200.0,
+            ..transform
+        };
+        assert_eq!(centered_position(&transform, 1920, 1080), (1160.0, 640.0));
+    }
+
+    #[test]
+    fn test_anchored_position() {
+        let transform = ItemTransform {
+            source_width: 1280.0,
+            source_height: 720.0,
+            scale_x: 0.25,
+            scale_y: 0.25,
+            alignment: Alignment::TOP | Alignment::LEFT,
+            ..Default::default()
+        };
+        let bottom_right = Alignment::BOTTOM | Alignment::RIGHT;
+        assert_eq!(
+            anchored_position(&transform, bottom_right, 40.0, 1920.0, 1080.0),
+            (1560.0, 860.0)
+        );
+        assert_eq!(
+            anchored_position(
+                &transform,
+                Alignment::TOP | Alignment::LEFT,
+                40.0,
+                1920.0,
+                1080.0
+            ),
+            (40.0, 40.0)
+        );
+        assert_eq!(
+            anchored_position(&transform, Alignment::CENTER, 40.0, 1920.0, 1080.0),
+            (800.0, 450.0)
+        );
+
+        let transform = ItemTransform {
+            alignment: Alignment::CENTER,
+            ..transform
+        };
+        assert_eq!(
+            anchored_position(&transform, bottom_right, 40.0, 1920.0, 1080.0),
+            (1720.0, 950.0)
+        );
+    }
+
+    #[test]
+    fn test_alignment_round_trip() {
+        for alignment in [
+            ItemAlignment::TopLeft,
+            ItemAlignment::Top,
+            ItemAlignment::TopRight,
+            ItemAlignment::Left,
+            ItemAlignment::Center,
+            ItemAlignment::Right,
+            ItemAlignment::BottomLeft,
+            ItemAlignment::Bottom,
+            ItemAlignment::BottomRight,
+        ] {
+            assert_eq!(ItemAlignment::from(Alignment::from(alignment)), alignment);
+        }
+    }
+
+    #[test]
+    fn test_tween_transform() {
+        let start = ItemTransform {
+            position_x: 100.0,
+            position_y: 50.0,
+            rotation: 10.0,
+            crop_left: 10,
+            ..Default::default()
+        };
+        let animation = ItemAnimation {
+            scene: "Main".to_string(),
+            items: vec!["Camera".to_string()],
+            to_position: Some((300.0, 150.0)),
+            to_scale: None,
+            to_rotation: None,
+            to_crop: Some([20, 0, 0, 5]),
+            duration: Duration::from_secs(1),
+            easing: Easing::Linear,
+            fps: 30,
+            on_abort: AbortSnap::End,
+        };
+
+        let halfway = tween_transform(&start, &animation, 0.5);
+        let position = halfway.position.unwrap();
+        assert_eq!((position.x, position.y), (Some(200.0), Some(100.0)));
+        let crop = halfway.crop.unwrap();
+        assert_eq!((crop.left, crop.bottom), (Some(15), Some(3)));
+        assert!(halfway.scale.is_none());
+        assert!(halfway.rotation.is_none());
+
+        let end = tween_transform(&start, &animation, 1.0);
+        let position = end.position.unwrap();
+        assert_eq!((position.x, position.y), (Some(300.0), Some(150.0)));
+        assert_eq!(end.crop.unwrap().left, Some(20));
+    }
+
+    #[test]
+    fn test_placed_transform() {
+        let cell = Rect {
+            x: 960.0,
+            y: 0.0,
+            width: 960.0,
+            height: 1080.0,
+        };
+        // Cropping the sides leaves a 1280x1080 source to fit
+        let current = ItemTransform {
+            source_width: 1920.0,
+            source_height: 1080.0,
+            crop_left: 320,
+            crop_right: 320,
+            ..Default::default()
+        };
+        let placed = placed_transform(&current, cell, Alignment::CENTER);
+        let position = placed.position.unwrap();
+        assert_eq!((position.x, position.y), (Some(960.0), Some(135.0)));
+        assert_eq!(placed.scale.unwrap().x, Some(0.75));
+        assert_eq!(placed.bounds.unwrap().r#type, Some(BoundsType::None));
+
+        let placed = placed_transform(&ItemTransform::default(), cell, Alignment::CENTER);
+        let bounds = placed.bounds.unwrap();
+        assert_eq!(bounds.r#type, Some(BoundsType::ScaleInner));
+        assert_eq!((bounds.width, bounds.height), (Some(960.0), Some(1080.0)));
+    }
+
+    #[test]
+    fn test_resolve_transform_value() {
+        let resolve = resolve_transform_value;
+        assert_eq!(
+            resolve("x", TransformValue::Absolute(-20.0), 100.0, None).unwrap(),
+            -20.0
+        );
+        assert_eq!(
+            resolve("x", TransformValue::Offset(20.0), 100.0, None).unwrap(),
+            120.0
+        );
+        assert_eq!(
+            resolve("x", TransformValue::Offset(-15.0), 100.0, None).unwrap(),
+            85.0
+        );
+        assert_eq!(
+            resolve("x", TransformValue::Factor(1.5), 2.0, None).unwrap(),
+            3.0
+        );
+        assert_eq!(
+            resolve("x", TransformValue::Percent(50.0), 0.0, Some(1920.0)).unwrap(),
+            960.0
+        );
+        assert!(resolve("scale-x", TransformValue::Percent(50.0), 1.0, None).is_err());
+    }
+
+    #[test]
+    fn test_alignment_name() {
+        assert_eq!(alignment_name(Alignment::CENTER), "center");
+        assert_eq!(alignment_name(Alignment::TOP | Alignment::LEFT), "top-left");
+        assert_eq!(alignment_name(Alignment::BOTTOM), "bottom");
+        assert_eq!(alignment_name(Alignment::RIGHT), "right");
+    }
+}
@@ -1,7 +1,22 @@
-use crate::cli::Scene;
-use crate::error::Result;
-use crate::handlers::CommandHandler;
+use crate::cli::{
+    ObsWebsocket, RotationStop, Scene, SceneCycle, SceneRotation, TransitionOverride,
+};
+use crate::connection::send_raw_request;
+use crate::error::{ObsCmdError, Result};
+use crate::handlers::scene_items::transform_request;
+use crate::handlers::{glob_match, CommandHandler};
+use futures_util::StreamExt;
+use obws::common::BlendMode;
+use obws::events::{Event, EventStream, OutputState};
+use obws::requests::config::{Realm, SetPersistentData};
+use obws::requests::filters::{Create as CreateFilter, SetEnabled as SetFilterEnabled};
+use obws::requests::inputs::{Create as CreateInput, InputId};
+use obws::requests::scene_items::{Duplicate, SetBlendMode, SetEnabled, SetLocked, SetTransform};
+use obws::requests::scenes::{SceneId, SetTransitionOverride};
+use obws::responses::scene_items::SourceType;
 use obws::Client;
+use serde_json::json;
+use std::collections::HashSet;
 use time::Duration;
 
 /// Handler for scene-related commands
@@ -9,6 +24,40 @@ pub struct SceneHandler {
     pub action: Scene,
 }
 
+/// Handler for `scene transition-override clear`
+pub struct ClearTransitionOverrideHandler {
+    pub scene_name: String,
+    /// The server `execute` is given a client for
+    pub server: ObsWebsocket,
+}
+
+#[async_trait::async_trait]
+impl CommandHandler for ClearTransitionOverrideHandler {
+    async fn execute(&self, _client: &Client) -> Result<()> {
+        println!(
+            "Clearing transition override for scene: {}",
+            self.scene_name
+        );
+        // Removing an override needs explicit nulls, which obws never sends
+        send_raw_request(
+            &self.server,
+            "SetSceneSceneTransitionOverride",
+            json!({
+                "sceneName": self.scene_name,
+                "transitionName": null,
+                "transitionDuration": null,
+            }),
+        )
+        .await?;
+        println!("Transition override cleared successfully");
+        Ok(())
+    }
+
+    fn description(&self) -> &'static str {
+        "Clear scene transition override"
+    }
+}
+
 #[async_trait::async_trait]
 impl CommandHandler for SceneHandler {
     async fn execute(&self, client: &Client) -> Result<()> {
@@ -22,15 +71,22 @@ impl CommandHandler for SceneHandler {
                     .map_err(|e| crate::error::ObsCmdError::ConnectionError(e))?;
                 println!("Current scene: {}", scene.id.name);
             }
-            Scene::Switch { scene_name } => {
-                println!("Switching to scene: {}", scene_name);
-                client
-                    .scenes()
-                    .set_current_program_scene(scene_name.as_str())
-                    .await
-                    .map_err(|e| crate::error::ObsCmdError::ConnectionError(e))?;
-                println!("Scene switched successfully");
+            Scene::Switch {
+                scene_name,
+                index,
+                transition,
+                duration,
+            } => {
+                let scene_name = match index {
+                    Some(index) => scene_at_index(client, *index).await?,
+                    None => scene_name.clone().unwrap_or_default(),
+                };
+                change_program_scene(client, &scene_name, transition.as_deref(), *duration).await?;
             }
+            Scene::Next(cycle) => cycle_scene(client, cycle, true).await?,
+            Scene::Prev(cycle) => cycle_scene(client, cycle, false).await?,
+            Scene::Back => go_back(client).await?,
+            Scene::Rotate(rotation) => rotate_scenes(client, rotation).await?,
             Scene::List => {
                 let scenes = client
                     .scenes()
@@ -75,6 +131,13 @@ impl CommandHandler for SceneHandler {
                     .map_err(|e| crate::error::ObsCmdError::ConnectionError(e))?;
                 println!("Scene renamed successfully");
             }
+            Scene::Clone {
+                scene_name,
+                new_name,
+                deep,
+            } => {
+                clone_scene(client, scene_name, new_name, *deep).await?;
+            }
 
             // Transition controls
             Scene::TransitionList => {
@@ -127,7 +190,47 @@ impl CommandHandler for SceneHandler {
                     .map_err(|e| crate::error::ObsCmdError::ConnectionError(e))?;
                 println!("Transition triggered successfully");
             }
-
+            Scene::TransitionOverride(TransitionOverride::Get { scene_name }) => {
+                let transition_override = client
+                    .scenes()
+                    .transition_override(SceneId::Name(scene_name))
+                    .await
+                    .map_err(|e| crate::error::ObsCmdError::ConnectionError(e))?;
+                println!("Transition override for scene '{}':", scene_name);
+                println!(
+                    "  Transition: {}",
+                    transition_override.name.as_deref().unwrap_or("(none)")
+                );
+                match transition_override.duration {
+                    Some(duration) => {
+                        println!("  Duration: {}ms", duration.whole_milliseconds())
+                    }
+                    None => println!("  Duration: (none)"),
+                }
+            }
+            Scene::TransitionOverride(TransitionOverride::Set {
+                scene_name,
+                transition,
+                duration,
+            }) => {
+                println!("Setting transition override for scene: {}", scene_name);
+                client
+                    .scenes()
+                    .set_transition_override(SetTransitionOverride {
+                        scene: SceneId::Name(scene_name),
+                        transition: transition.as_deref(),
+                        duration: duration.map(|ms| Duration::milliseconds(ms as i64)),
+                    })
+                    .await
+                    .map_err(|e| crate::error::ObsCmdError::ConnectionError(e))?;
+                println!("Transition override set successfully");
+            }
+            Scene::TransitionOverride(TransitionOverride::Clear { .. }) => {
+                // Clearing needs the server address and is routed in handle_commands()
+                panic!(
+                    "Transition override clear should be handled by ClearTransitionOverrideHandler"
+                );
+            }
             // Studio mode controls
             Scene::StudioModeStatus => {
                 let studio_mode_enabled = client
@@ -207,6 +310,36 @@ impl CommandHandler for SceneHandler {
                     .map_err(|e| crate::error::ObsCmdError::ConnectionError(e))?;
                 println!("Preview scene set successfully");
             }
+
+            // Studio mode workflow
+            Scene::Stage { scene_name } => {
+                let studio_mode_enabled = client
+                    .ui()
+                    .studio_mode_enabled()
+                    .await
+                    .map_err(|e| crate::error::ObsCmdError::ConnectionError(e))?;
+                if !studio_mode_enabled {
+                    println!("Enabling studio mode");
+                    client
+                        .ui()
+                        .set_studio_mode_enabled(true)
+                        .await
+                        .map_err(|e| crate::error::ObsCmdError::ConnectionError(e))?;
+                }
+                println!("Staging scene: {}", scene_name);
+                client
+                    .scenes()
+                    .set_current_preview_scene(scene_name.as_str())
+                    .await
+                    .map_err(|e| crate::error::ObsCmdError::ConnectionError(e))?;
+                println!("Scene staged in preview");
+            }
+            Scene::Take {
+                transition,
+                duration,
+                wait,
+            } => take_preview(client, transition.as_deref(), *duration, *wait).await?,
+            Scene::Swap => swap_program_and_preview(client).await?,
         };
         Ok(())
     }
@@ -215,15 +348,29 @@ impl CommandHandler for SceneHandler {
         match &self.action {
             Scene::Current => "Get current scene",
             Scene::Switch { .. } => "Switch to scene",
+            Scene::Next(_) => "Switch to next scene",
+            Scene::Prev(_) => "Switch to previous scene",
+            Scene::Back => "Switch back to previous scene",
+            Scene::Rotate(_) => "Rotate scenes",
             Scene::List => "List all scenes",
             Scene::Create { .. } => "Create new scene",
             Scene::Remove { .. } => "Remove scene",
             Scene::Rename { .. } => "Rename scene",
+            Scene::Clone { .. } => "Clone scene",
             Scene::TransitionList => "List available transitions",
             Scene::TransitionCurrent => "Get current transition",
             Scene::TransitionSet { .. } => "Set current transition",
             Scene::TransitionDuration { .. } => "Set transition duration",
             Scene::TransitionTrigger => "Trigger transition",
+            Scene::TransitionOverride(TransitionOverride::Get { .. }) => {
+                "Get scene transition override"
+            }
+            Scene::TransitionOverride(TransitionOverride::Set { .. }) => {
+                "Set scene transition override"
+            }
+            Scene::TransitionOverride(TransitionOverride::Clear { .. }) => {
+                "Clear scene transition override"
+            }
             Scene::StudioModeStatus => "Get studio mode status",
             Scene::StudioModeEnable => "Enable studio mode",
             Scene::StudioModeDisable => "Disable studio mode",
@@ -231,6 +378,892 @@ impl CommandHandler for SceneHandler {
             Scene::StudioModeTransition => "Trigger studio mode transition",
             Scene::PreviewCurrent => "Get current preview scene",
             Scene::PreviewSet { .. } => "Set preview scene",
+            Scene::Stage { .. } => "Stage scene in preview",
+            Scene::Take { .. } => "Take preview to program",
+            Scene::Swap => "Swap program and preview scenes",
+        }
+    }
+}
+
+/// Persistent data slot holding the program scenes left by obs-cmd switches,
+/// most recent last
+const SCENE_HISTORY_SLOT: &str = "obs-cmd-scene-history";
+
+/// Maximum number of scenes kept in the scene history
+const SCENE_HISTORY_LIMIT: usize = 20;
+
+/// Switches the program scene and records the scene being left in the
+/// scene history used by `scene back`
+async fn change_program_scene(
+    client: &Client,
+    scene_name: &str,
+    transition: Option<&str>,
+    duration_ms: Option<u64>,
+) -> Result<()> {
+    let previous = client
+        .scenes()
+        .current_program_scene()
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?
+        .id
+        .name;
+
+    if transition.is_some() || duration_ms.is_some() {
+        println!(
+            "Switching to scene: {} (transition: {}, duration: {})",
+            scene_name,
+            transition.unwrap_or("current"),
+            duration_ms
+                .map(|ms| format!("{}ms", ms))
+                .unwrap_or_else(|| "unchanged".to_string())
+        );
+        let switch = async {
+            client
+                .scenes()
+                .set_current_program_scene(scene_name)
+                .await
+                .map_err(|e| ObsCmdError::ConnectionError(e))
+        };
+        cut_with_transition(client, transition, duration_ms, switch).await?;
+        println!("Scene switched successfully");
+    } else {
+        println!("Switching to scene: {}", scene_name);
+        client
+            .scenes()
+            .set_current_program_scene(scene_name)
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+        println!("Scene switched successfully");
+    }
+
+    record_scene_history(client, previous, scene_name).await
+}
+
+/// Remembers the program scene that was left for `scene back`
+async fn record_scene_history(client: &Client, previous: String, current: &str) -> Result<()> {
+    if previous == current {
+        return Ok(());
+    }
+    let mut history = load_scene_history(client).await?;
+    if history.last() != Some(&previous) {
+        history.push(previous);
+    }
+    save_scene_history(client, &history).await
+}
+
+/// Returns scene names in the order shown in the OBS scene list, top first
+async fn scene_order(client: &Client) -> Result<Vec<String>> {
+    let mut scenes = client
+        .scenes()
+        .list()
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?
+        .scenes;
+    // sceneIndex 0 is the bottom of the OBS scene list
+    scenes.sort_by_key(|scene| std::cmp::Reverse(scene.index));
+    Ok(scenes.into_iter().map(|scene| scene.id.name).collect())
+}
+
+/// Resolves a 1-based position in the OBS scene list to a scene name
+async fn scene_at_index(client: &Client, index: u32) -> Result<String> {
+    let order = scene_order(client).await?;
+    let count = order.len();
+    order
+        .into_iter()
+        .nth(index as usize - 1)
+        .ok_or(ObsCmdError::SceneIndexOutOfRange { index, count })
+}
+
+/// Switches to the neighbouring scene of the current program scene
+async fn cycle_scene(client: &Client, cycle: &SceneCycle, forward: bool) -> Result<()> {
+    let order = scene_order(client).await?;
+    let current = client
+        .scenes()
+        .current_program_scene()
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?
+        .id
+        .name;
+
+    match step_scene(&order, &current, forward, cycle.wrap, &cycle.skip_patterns) {
+        Some(next) => change_program_scene(client, next, None, None).await,
+        None if cycle.wrap => {
+            println!("No other scene to switch to");
+            Ok(())
         }
+        None => {
+            println!(
+                "Already at the {} scene, use --wrap to continue from the other end",
+                if forward { "last" } else { "first" }
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Finds the next scene after `current` in the given direction, skipping
+/// scenes that match any of the skip patterns
+fn step_scene<'a>(
+    order: &'a [String],
+    current: &str,
+    forward: bool,
+    wrap: bool,
+    skip_patterns: &[String],
+) -> Option<&'a str> {
+    let len = order.len() as isize;
+    let step = if forward { 1 } else { -1 };
+    let mut position = order
+        .iter()
+        .position(|name| name == current)
+        .map(|p| p as isize)
+        .unwrap_or(if forward { -1 } else { len });
+
+    for _ in 0..len {
+        position += step;
+        if !(0..len).contains(&position) {
+            if !wrap {
+                return None;
+            }
+            position = position.rem_euclid(len);
+        }
+        let name = &order[position as usize];
+        if name == current {
+            return None;
+        }
+        if !skip_patterns
+            .iter()
+            .any(|pattern| glob_match(pattern, name))
+        {
+            return Some(name);
+        }
+    }
+    None
+}
+
+/// Switches back to the most recent scene in the history that still exists
+async fn go_back(client: &Client) -> Result<()> {
+    let order = scene_order(client).await?;
+    let current = client
+        .scenes()
+        .current_program_scene()
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?
+        .id
+        .name;
+
+    let mut history = load_scene_history(client).await?;
+    while let Some(previous) = history.pop() {
+        if previous == current || !order.contains(&previous) {
+            continue;
+        }
+        println!("Going back to scene: {}", previous);
+        client
+            .scenes()
+            .set_current_program_scene(previous.as_str())
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+        save_scene_history(client, &history).await?;
+        println!("Scene switched successfully");
+        return Ok(());
+    }
+
+    save_scene_history(client, &history).await?;
+    Err(ObsCmdError::NoSceneHistory)
+}
+
+/// Cycles the program scene through the rotation until interrupted, then
+/// restores the scene and transition that were live before it started
+async fn rotate_scenes(client: &Client, rotation: &SceneRotation) -> Result<()> {
+    let original_scene = client
+        .scenes()
+        .current_program_scene()
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?
+        .id
+        .name;
+    let original_transition = match &rotation.transition {
+        Some(transition) => {
+            let previous = client
+                .transitions()
+                .current()
+                .await
+                .map_err(|e| ObsCmdError::ConnectionError(e))?;
+            client
+                .transitions()
+                .set_current(transition)
+                .await
+                .map_err(|e| ObsCmdError::ConnectionError(e))?;
+            Some((previous.id.name, previous.duration))
+        }
+        None => None,
+    };
+    let mut events = match rotation.until_event {
+        Some(_) => Some(
+            client
+                .events()
+                .map_err(|e| ObsCmdError::ConnectionError(e))?,
+        ),
+        None => None,
+    };
+
+    println!(
+        "Rotating through {} scene(s) every {:.2}s, press Ctrl-C to stop",
+        rotation.scenes.len(),
+        rotation.interval.as_secs_f64()
+    );
+
+    let result = tokio::select! {
+        result = run_rotation(client, rotation) => result,
+        _ = tokio::signal::ctrl_c() => {
+            println!("Rotation interrupted");
+            Ok(())
+        }
+        _ = wait_for_rotation_stop(events.as_mut(), rotation.until_event) => {
+            println!("Rotation stopped by event");
+            Ok(())
+        }
+    };
+
+    // Restore the original state even if the rotation failed. Each part is
+    // restored even if the other one fails, and errors are reported after
+    let restored_transition = match original_transition {
+        Some((transition, duration)) => restore_transition(client, &transition, duration).await,
+        None => Ok(()),
+    };
+    let restored_scene = restore_scene(client, &original_scene).await;
+
+    result.and(restored_transition).and(restored_scene)
+}
+
+/// Makes a transition current again, with the duration it had
+async fn restore_transition(
+    client: &Client,
+    transition: &str,
+    duration: Option<Duration>,
+) -> Result<()> {
+    client
+        .transitions()
+        .set_current(transition)
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    if let Some(duration) = duration {
+        client
+            .transitions()
+            .set_current_duration(duration)
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    }
+    Ok(())
+}
+
+/// Switches back to the scene that was live before, if it still exists
+async fn restore_scene(client: &Client, scene: &str) -> Result<()> {
+    if scene_order(client).await?.iter().any(|name| name == scene) {
+        client
+            .scenes()
+            .set_current_program_scene(scene)
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+        println!("Restored scene: {}", scene);
+    } else {
+        println!(
+            "Scene '{}' no longer exists, leaving the program scene unchanged",
+            scene
+        );
+    }
+    Ok(())
+}
+
+/// Shows the rotation scenes one after another, forever, skipping scenes
+/// that no longer exist
+async fn run_rotation(client: &Client, rotation: &SceneRotation) -> Result<()> {
+    let mut last: Option<String> = None;
+    loop {
+        let mut round = rotation.scenes.clone();
+        if rotation.shuffle {
+            fastrand::shuffle(&mut round);
+            // Avoid showing the same scene twice in a row across rounds
+            if round.len() > 1 && round.first() == last.as_ref() {
+                let end = round.len() - 1;
+                round.swap(0, end);
+            }
+        }
+
+        let mut shown = 0;
+        for scene in round {
+            if !scene_order(client).await?.contains(&scene) {
+                println!("Skipping missing scene: {}", scene);
+                continue;
+            }
+            println!("Showing scene: {}", scene);
+            client
+                .scenes()
+                .set_current_program_scene(scene.as_str())
+                .await
+                .map_err(|e| ObsCmdError::ConnectionError(e))?;
+            shown += 1;
+            last = Some(scene);
+            tokio::time::sleep(rotation.interval).await;
+        }
+
+        if shown == 0 {
+            return Err(ObsCmdError::NoRotationScenes);
+        }
+    }
+}
+
+/// Waits for the event that ends a rotation, or forever if there is none
+async fn wait_for_rotation_stop(events: Option<&mut EventStream>, stop: Option<RotationStop>) {
+    let (Some(events), Some(stop)) = (events, stop) else {
+        return std::future::pending().await;
+    };
+
+    while let Some(event) = events.next().await {
+        match (stop, event) {
+            (
+                RotationStop::StreamStopped,
+                Event::StreamStateChanged {
+                    state: OutputState::Stopped,
+                    ..
+                },
+            )
+            | (
+                RotationStop::RecordStopped,
+                Event::RecordStateChanged {
+                    state: OutputState::Stopped,
+                    ..
+                },
+            ) => return,
+            _ => {}
+        }
+    }
+    std::future::pending().await
+}
+
+async fn load_scene_history(client: &Client) -> Result<Vec<String>> {
+    let data = client
+        .config()
+        .get_persistent_data(Realm::Global, SCENE_HISTORY_SLOT)
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    Ok(data
+        .get("slotValue")
+        .and_then(|value| value.as_array())
+        .map(|scenes| {
+            scenes
+                .iter()
+                .filter_map(|scene| scene.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+async fn save_scene_history(client: &Client, history: &[String]) -> Result<()> {
+    let kept = &history[history.len().saturating_sub(SCENE_HISTORY_LIMIT)..];
+    client
+        .config()
+        .set_persistent_data(SetPersistentData {
+            realm: Realm::Global,
+            slot_name: SCENE_HISTORY_SLOT,
+            slot_value: &json!(kept),
+        })
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))
+}
+
+/// Returns the current program and preview scene names, failing if studio
+/// mode is off
+async fn studio_scenes(client: &Client) -> Result<(String, String)> {
+    let studio_mode_enabled = client
+        .ui()
+        .studio_mode_enabled()
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    if !studio_mode_enabled {
+        return Err(ObsCmdError::StudioModeNotEnabled);
+    }
+
+    let program = client
+        .scenes()
+        .current_program_scene()
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?
+        .id
+        .name;
+    let preview = client
+        .scenes()
+        .current_preview_scene()
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?
+        .id
+        .name;
+    Ok((program, preview))
+}
+
+/// Transitions the preview scene to program, optionally with a one-off
+/// transition and/or waiting for the transition to finish
+async fn take_preview(
+    client: &Client,
+    transition: Option<&str>,
+    duration_ms: Option<u64>,
+    wait: bool,
+) -> Result<()> {
+    let (program, preview) = studio_scenes(client).await?;
+    println!("Taking scene '{}' to program", preview);
+
+    let trigger = async {
+        client
+            .transitions()
+            .trigger()
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))
+    };
+
+    if transition.is_some() || duration_ms.is_some() {
+        // The previous transition is restored only after the cut, so this
+        // always waits
+        cut_with_transition(client, transition, duration_ms, trigger).await?;
+    } else if wait {
+        let expected = client
+            .transitions()
+            .current()
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?
+            .duration
+            .and_then(|d| std::time::Duration::try_from(d).ok())
+            .unwrap_or_default();
+        let mut events = client
+            .events()
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+        trigger.await?;
+        if !wait_for_transition_end(&mut events, expected + std::time::Duration::from_secs(5)).await
+        {
+            println!("Timed out waiting for the transition to finish");
+        }
+    } else {
+        trigger.await?;
+    }
+
+    println!("Take completed");
+    record_scene_history(client, program, &preview).await
+}
+
+/// Puts the preview scene on program and the program scene in preview
+async fn swap_program_and_preview(client: &Client) -> Result<()> {
+    let (program, preview) = studio_scenes(client).await?;
+    println!("Swapping program '{}' with preview '{}'", program, preview);
+
+    client
+        .scenes()
+        .set_current_program_scene(preview.as_str())
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    client
+        .scenes()
+        .set_current_preview_scene(program.as_str())
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+
+    println!("Program and preview swapped");
+    record_scene_history(client, program, &preview).await
+}
+
+/// Performs a scene cut using a one-off transition and/or duration, waits
+/// for the transition to finish, then restores the previous transition
+/// settings
+async fn cut_with_transition<F>(
+    client: &Client,
+    transition: Option<&str>,
+    duration_ms: Option<u64>,
+    cut: F,
+) -> Result<()>
+where
+    F: std::future::Future<Output = Result<()>>,
+{
+    let previous = client
+        .transitions()
+        .current()
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+
+    if let Some(transition) = transition {
+        client
+            .transitions()
+            .set_current(transition)
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    }
+    if let Some(ms) = duration_ms {
+        client
+            .transitions()
+            .set_current_duration(Duration::milliseconds(ms as i64))
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    }
+
+    let mut events = client
+        .events()
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    let result = cut.await;
+
+    if result.is_ok() {
+        let expected = duration_ms
+            .map(std::time::Duration::from_millis)
+            .or_else(|| {
+                previous
+                    .duration
+                    .and_then(|d| std::time::Duration::try_from(d).ok())
+            })
+            .unwrap_or_default();
+        wait_for_transition_end(&mut events, expected + std::time::Duration::from_secs(5)).await;
+    }
+
+    // Restore the previous transition even if the cut failed
+    client
+        .transitions()
+        .set_current(&previous.id.name)
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    if let Some(duration) = previous.duration {
+        client
+            .transitions()
+            .set_current_duration(duration)
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    }
+
+    result?;
+    println!("Restored transition '{}'", previous.id.name);
+    Ok(())
+}
+
+/// Waits until the running scene transition has fully played out.
+///
+/// Returns `false` if no transition end was seen before the timeout.
+pub(crate) async fn wait_for_transition_end(
+    events: &mut EventStream,
+    timeout: std::time::Duration,
+) -> bool {
+    let ended = async {
+        while let Some(event) = events.next().await {
+            // VideoEnded also covers stingers, whose Ended event only marks the cut point
+            if let Event::SceneTransitionVideoEnded { .. } = event {
+                return true;
+            }
+        }
+        false
+    };
+
+    tokio::time::timeout(timeout, ended).await.unwrap_or(false)
+}
+
+/// Recreates a scene item by item, since obs-websocket has no request to
+/// duplicate a whole scene. Items are copied bottom to top so the new scene
+/// keeps their order. If a step fails the half-built scene is removed again
+async fn clone_scene(client: &Client, scene: &str, new_name: &str, deep: bool) -> Result<()> {
+    let items = client
+        .scene_items()
+        .list(SceneId::Name(scene))
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+
+    // obs-websocket cannot create groups, so a deep clone could only share
+    // them and the inputs inside them
+    if deep {
+        if let Some(group) = items.iter().find(|item| item.is_group.unwrap_or(false)) {
+            return Err(ObsCmdError::DeepCloneGroup {
+                scene: scene.to_string(),
+                group: group.source_name.clone(),
+            });
+        }
+    }
+
+    // Source names are shared by inputs and scenes
+    let mut taken: HashSet<String> = client
+        .inputs()
+        .list(None)
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?
+        .into_iter()
+        .map(|input| input.id.name)
+        .collect();
+    taken.extend(
+        client
+            .scenes()
+            .list()
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?
+            .scenes
+            .into_iter()
+            .map(|scene| scene.id.name),
+    );
+    taken.insert(new_name.to_string());
+
+    client
+        .scenes()
+        .create(new_name)
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    println!("Cloning scene '{}' to '{}':", scene, new_name);
+
+    let mut created = Vec::new();
+    for item in &items {
+        let copied = clone_scene_item(
+            client,
+            scene,
+            new_name,
+            item,
+            deep,
+            &mut taken,
+            &mut created,
+        )
+        .await;
+        match copied {
+            Ok(description) => println!("  - {}: {}", item.source_name, description),
+            Err(e) => {
+                client
+                    .scenes()
+                    .remove(SceneId::Name(new_name))
+                    .await
+                    .map_err(|e| ObsCmdError::ConnectionError(e))?;
+                println!("Removed incomplete scene '{}'", new_name);
+                for input in &created {
+                    client
+                        .inputs()
+                        .remove(InputId::Name(input))
+                        .await
+                        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+                    println!("Removed copied input '{}'", input);
+                }
+                return Err(e);
+            }
+        }
+    }
+
+    println!(
+        "Cloned {} item(s) from scene '{}' to '{}'",
+        items.len(),
+        scene,
+        new_name
+    );
+    Ok(())
+}
+
+/// Copies one scene item into the new scene with its transform, enabled and
+/// locked state and blend mode, returning how it was copied. Inputs copied
+/// for a deep clone are recorded in `created`
+async fn clone_scene_item(
+    client: &Client,
+    scene: &str,
+    new_name: &str,
+    item: &obws::responses::scene_items::SceneItem,
+    deep: bool,
+    taken: &mut HashSet<String>,
+    created: &mut Vec<String>,
+) -> Result<String> {
+    let source = SceneId::Name(scene);
+    let target = SceneId::Name(new_name);
+    let is_group = item.is_group.unwrap_or(false);
+    let private_copy = deep && item.source_type == SourceType::Input;
+
+    let (item_id, description) = if private_copy {
+        let input_name = unique_source_name(&item.source_name, taken);
+        taken.insert(input_name.clone());
+        let item_id = copy_input(client, &item.source_name, &input_name, target, created).await?;
+        client
+            .scene_items()
+            .set_enabled(SetEnabled {
+                scene: target,
+                item_id,
+                enabled: client
+                    .scene_items()
+                    .enabled(source, item.id)
+                    .await
+                    .map_err(|e| ObsCmdError::ConnectionError(e))?,
+            })
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+        let transform = client
+            .scene_items()
+            .transform(source, item.id)
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+        client
+            .scene_items()
+            .set_transform(SetTransform {
+                scene: target,
+                item_id,
+                transform: transform_request(&transform),
+            })
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+        (item_id, format!("copied as input '{}'", input_name))
+    } else {
+        // OBS copies the transform, crop and enabled state along with the item
+        let item_id = client
+            .scene_items()
+            .duplicate(Duplicate {
+                scene: source,
+                item_id: item.id,
+                destination: Some(target.into()),
+            })
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+        let description = if is_group { "group shared" } else { "shared" };
+        (item_id, description.to_string())
+    };
+
+    let locked = client
+        .scene_items()
+        .locked(source, item.id)
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    if locked {
+        client
+            .scene_items()
+            .set_locked(SetLocked {
+                scene: target,
+                item_id,
+                locked,
+            })
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    }
+    let mode = client
+        .scene_items()
+        .blend_mode(source, item.id)
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    if mode != BlendMode::Normal {
+        client
+            .scene_items()
+            .set_blend_mode(SetBlendMode {
+                scene: target,
+                item_id,
+                mode,
+            })
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    }
+
+    Ok(description)
+}
+
+/// Creates a new input in the scene with the kind, settings and filter
+/// chain of an existing one, returning the new scene item's ID. The new
+/// input's name is pushed to `created` as soon as it exists
+async fn copy_input(
+    client: &Client,
+    input: &str,
+    new_input: &str,
+    scene: SceneId<'_>,
+    created: &mut Vec<String>,
+) -> Result<i64> {
+    let settings = client
+        .inputs()
+        .settings::<serde_json::Value>(InputId::Name(input))
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    let new_item = client
+        .inputs()
+        .create(CreateInput {
+            scene,
+            input: new_input,
+            kind: &settings.kind,
+            settings: Some(&settings.settings),
+            enabled: Some(false),
+        })
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    created.push(new_input.to_string());
+
+    let mut filters = client
+        .filters()
+        .list(input.into())
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    filters.sort_by_key(|filter| filter.index);
+    for filter in filters {
+        client
+            .filters()
+            .create(CreateFilter {
+                source: new_input.into(),
+                filter: &filter.name,
+                kind: &filter.kind,
+                settings: Some(&filter.settings),
+            })
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+        if !filter.enabled {
+            client
+                .filters()
+                .set_enabled(SetFilterEnabled {
+                    source: new_input.into(),
+                    filter: &filter.name,
+                    enabled: false,
+                })
+                .await
+                .map_err(|e| ObsCmdError::ConnectionError(e))?;
+        }
+    }
+
+    Ok(new_item.scene_item_id)
+}
+
+/// First free name of the form "Name 2", "Name 3", ... the way OBS names
+/// duplicated sources
+fn unique_source_name(name: &str, taken: &HashSet<String>) -> String {
+    (2..)
+        .map(|n| format!("{} {}", name, n))
+        .find(|candidate| !taken.contains(candidate))
+        .unwrap_or_else(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{step_scene, unique_source_name};
+    use std::collections::HashSet;
+
+    fn scenes(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_step_scene() {
+        let order = scenes(&["Intro", "[nested] Cam", "Live", "Outro"]);
+        let skip = vec!["[nested] *".to_string()];
+
+        assert_eq!(
+            step_scene(&order, "Intro", true, false, &skip),
+            Some("Live")
+        );
+        assert_eq!(
+            step_scene(&order, "Live", false, false, &skip),
+            Some("Intro")
+        );
+        assert_eq!(step_scene(&order, "Outro", true, false, &skip), None);
+        assert_eq!(
+            step_scene(&order, "Outro", true, true, &skip),
+            Some("Intro")
+        );
+        assert_eq!(step_scene(&order, "Intro", false, true, &[]), Some("Outro"));
+        assert_eq!(
+            step_scene(&order, "[nested] Cam", true, false, &skip),
+            Some("Live")
+        );
+        assert_eq!(
+            step_scene(&scenes(&["Solo"]), "Solo", true, true, &[]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_unique_source_name() {
+        let taken: HashSet<String> = ["Camera", "Camera 2", "Mic"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+        assert_eq!(unique_source_name("Camera", &taken), "Camera 3");
+        assert_eq!(unique_source_name("Mic", &taken), "Mic 2");
     }
 }
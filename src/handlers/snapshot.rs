@@ -0,0 +1,47 @@
+use crate::cli::Snapshot;
+use crate::error::{ObsCmdError, Result};
+use crate::handlers::CommandHandler;
+use crate::state::ObsState;
+use obws::Client;
+
+/// Handler for snapshot commands
+pub struct SnapshotHandler {
+    pub action: Snapshot,
+}
+
+#[async_trait::async_trait]
+impl CommandHandler for SnapshotHandler {
+    async fn execute(&self, client: &Client) -> Result<()> {
+        match &self.action {
+            Snapshot::Export { output } => {
+                let state = ObsState::capture(client).await?;
+                let json = serde_json::to_string_pretty(&state)?;
+
+                match output {
+                    Some(path) => {
+                        std::fs::write(path, json + "\n").map_err(|e| {
+                            ObsCmdError::StateFileError {
+                                path: path.clone(),
+                                message: e.to_string(),
+                            }
+                        })?;
+                        println!(
+                            "Saved snapshot of {} scene(s) and {} input(s) to {}",
+                            state.scenes.len(),
+                            state.inputs.len(),
+                            path.display()
+                        );
+                    }
+                    None => println!("{}", json),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn description(&self) -> &'static str {
+        match &self.action {
+            Snapshot::Export { .. } => "Export OBS state snapshot",
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use crate::animation::{lerp, Timeline};
+use crate::cli::Transition;
+use crate::error::{ObsCmdError, Result};
+use crate::handlers::CommandHandler;
+use obws::Client;
+
+/// Handler for transition configuration commands
+pub struct TransitionHandler {
+    pub action: Transition,
+}
+
+#[async_trait::async_trait]
+impl CommandHandler for TransitionHandler {
+    async fn execute(&self, client: &Client) -> Result<()> {
+        match &self.action {
+            Transition::Kinds => {
+                let kinds = client
+                    .transitions()
+                    .list_kinds()
+                    .await
+                    .map_err(|e| ObsCmdError::ConnectionError(e))?;
+                println!("Available transition kinds:");
+                for kind in kinds {
+                    println!("  - {}", kind);
+                }
+            }
+            Transition::Settings { settings, replace } => {
+                let settings: serde_json::Value = serde_json::from_str(settings)?;
+                if !settings.is_object() {
+                    return Err(ObsCmdError::InvalidJson(serde::de::Error::custom(
+                        "transition settings must be a JSON object",
+                    )));
+                }
+
+                println!("Updating current transition settings...");
+                client
+                    .transitions()
+                    .set_current_settings(&settings, Some(!replace))
+                    .await
+                    .map_err(|e| ObsCmdError::ConnectionError(e))?;
+                println!("Transition settings updated successfully");
+            }
+            Transition::Cursor => {
+                let cursor = client
+                    .transitions()
+                    .current_cursor()
+                    .await
+                    .map_err(|e| ObsCmdError::ConnectionError(e))?;
+                println!("Transition cursor: {:.3}", cursor);
+            }
+            Transition::Tbar {
+                position: Some(position),
+                release,
+                ..
+            } => {
+                println!("Moving T-bar to {:.3}", position);
+                client
+                    .transitions()
+                    .set_tbar_position(*position as f32, Some(*release))
+                    .await
+                    .map_err(|e| ObsCmdError::ConnectionError(e))?;
+                println!("T-bar moved successfully");
+            }
+            Transition::Tbar {
+                position: None,
+                from,
+                to,
+                duration,
+                easing,
+                fps,
+                release,
+            } => {
+                let to = to.unwrap_or(1.0);
+                println!(
+                    "Sliding T-bar from {:.3} to {:.3} over {:.2}s",
+                    from,
+                    to,
+                    duration.as_secs_f64()
+                );
+
+                let mut timeline = Timeline::new(*duration, *fps, *easing);
+                client
+                    .transitions()
+                    .set_tbar_position(*from as f32, Some(false))
+                    .await
+                    .map_err(|e| ObsCmdError::ConnectionError(e))?;
+                while let Some(t) = timeline.next_frame().await {
+                    // Only the final position may release the T-bar
+                    let release = t >= 1.0 && *release;
+                    client
+                        .transitions()
+                        .set_tbar_position(lerp(*from, to, t) as f32, Some(release))
+                        .await
+                        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+                }
+                println!("T-bar slide completed");
+            }
+        }
+        Ok(())
+    }
+
+    fn description(&self) -> &'static str {
+        match &self.action {
+            Transition::Kinds => "List transition kinds",
+            Transition::Settings { .. } => "Set current transition settings",
+            Transition::Cursor => "Get transition cursor",
+            Transition::Tbar { .. } => "Move studio mode T-bar",
+        }
+    }
+}
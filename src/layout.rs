@@ -0,0 +1,165 @@
+/// A rectangle on the canvas, in pixels from the top-left corner.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Rect {
+    /// The area of a canvas inside a margin on every side.
+    pub fn canvas(width: f32, height: f32, margin: f32) -> Self {
+        Self {
+            x: margin,
+            y: margin,
+            width: (width - 2.0 * margin).max(0.0),
+            height: (height - 2.0 * margin).max(0.0),
+        }
+    }
+}
+
+/// Splits an area into `count` equally sized cells, filled row by row.
+///
+/// Without an explicit column count the grid is as square as possible
+/// (2 items side by side, 4 in a 2x2 grid, 5 or 6 in a 3x2 grid).
+pub fn grid_cells(area: Rect, count: usize, columns: Option<u32>, gap: f32) -> Vec<Rect> {
+    if count == 0 {
+        return Vec::new();
+    }
+    let columns = columns
+        .map(|columns| columns as usize)
+        .unwrap_or_else(|| (count as f64).sqrt().ceil() as usize)
+        .clamp(1, count);
+    let rows = count.div_ceil(columns);
+
+    let width = ((area.width - gap * (columns - 1) as f32) / columns as f32).max(0.0);
+    let height = ((area.height - gap * (rows - 1) as f32) / rows as f32).max(0.0);
+
+    (0..count)
+        .map(|i| Rect {
+            x: area.x + (i % columns) as f32 * (width + gap),
+            y: area.y + (i / columns) as f32 * (height + gap),
+            width,
+            height,
+        })
+        .collect()
+}
+
+/// The inset cell of a picture-in-picture layout: `size` percent of the
+/// canvas, placed at the fractional corner position `(anchor_x, anchor_y)`
+/// of the area (0.0 is left/top, 1.0 is right/bottom).
+pub fn inset_cell(
+    area: Rect,
+    canvas_width: f32,
+    canvas_height: f32,
+    size: f32,
+    anchor: (f32, f32),
+) -> Rect {
+    let width = canvas_width * size / 100.0;
+    let height = canvas_height * size / 100.0;
+    Rect {
+        x: area.x + anchor.0 * (area.width - width),
+        y: area.y + anchor.1 * (area.height - height),
+        width,
+        height,
+    }
+}
+
+/// Scales a source of the given size to fit inside the cell without
+/// distortion, returning the top-left position and the scale. `anchor`
+/// places the scaled source inside the cell like [`inset_cell`] does.
+///
+/// Returns `None` when the source has no size yet.
+pub fn fit_in_cell(
+    source_width: f32,
+    source_height: f32,
+    cell: Rect,
+    anchor: (f32, f32),
+) -> Option<(f32, f32, f32)> {
+    if source_width <= 0.0 || source_height <= 0.0 {
+        return None;
+    }
+    let scale = (cell.width / source_width).min(cell.height / source_height);
+    let width = source_width * scale;
+    let height = source_height * scale;
+    Some((
+        cell.x + anchor.0 * (cell.width - width),
+        cell.y + anchor.1 * (cell.height - height),
+        scale,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fit_in_cell, grid_cells, inset_cell, Rect};
+
+    #[test]
+    fn test_grid_cells() {
+        let canvas = Rect::canvas(1920.0, 1080.0, 32.0);
+        let cells = grid_cells(canvas, 4, None, 16.0);
+        assert_eq!(cells.len(), 4);
+        assert_eq!(
+            cells[0],
+            Rect {
+                x: 32.0,
+                y: 32.0,
+                width: 920.0,
+                height: 500.0
+            }
+        );
+        assert_eq!((cells[1].x, cells[1].y), (968.0, 32.0));
+        assert_eq!((cells[3].x, cells[3].y), (968.0, 548.0));
+
+        // Five items need three columns and two rows
+        let cells = grid_cells(Rect::canvas(1920.0, 1080.0, 0.0), 5, None, 0.0);
+        assert_eq!((cells[4].x, cells[4].y), (640.0, 540.0));
+        assert_eq!(cells[4].width, 640.0);
+
+        // Side by side is a single row of two
+        let cells = grid_cells(Rect::canvas(1920.0, 1080.0, 0.0), 2, None, 0.0);
+        assert_eq!(cells[1].x, 960.0);
+        assert_eq!(cells[1].height, 1080.0);
+
+        let cells = grid_cells(Rect::canvas(1920.0, 1080.0, 0.0), 3, Some(1), 0.0);
+        assert_eq!(cells[2].y, 720.0);
+        assert!(grid_cells(canvas, 0, None, 16.0).is_empty());
+    }
+
+    #[test]
+    fn test_inset_cell() {
+        let area = Rect::canvas(1920.0, 1080.0, 32.0);
+        let cell = inset_cell(area, 1920.0, 1080.0, 25.0, (1.0, 1.0));
+        assert_eq!(
+            cell,
+            Rect {
+                x: 1408.0,
+                y: 778.0,
+                width: 480.0,
+                height: 270.0
+            }
+        );
+        let cell = inset_cell(area, 1920.0, 1080.0, 25.0, (0.0, 0.0));
+        assert_eq!((cell.x, cell.y), (32.0, 32.0));
+    }
+
+    #[test]
+    fn test_fit_in_cell() {
+        let cell = Rect {
+            x: 0.0,
+            y: 0.0,
+            width: 960.0,
+            height: 1080.0,
+        };
+        // A 16:9 source in a tall cell is letterboxed in the middle
+        assert_eq!(
+            fit_in_cell(1920.0, 1080.0, cell, (0.5, 0.5)),
+            Some((0.0, 270.0, 0.5))
+        );
+        assert_eq!(
+            fit_in_cell(1920.0, 1080.0, cell, (0.5, 1.0)),
+            Some((0.0, 540.0, 0.5))
+        );
+        assert_eq!(fit_in_cell(0.0, 0.0, cell, (0.5, 0.5)), None);
+    }
+}
@@ -0,0 +1,365 @@
+use crate::error::{ObsCmdError, Result};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        })
+    }
+}
+
+/// A problem found in a scene collection, reported for one source
+#[derive(Clone, Debug, PartialEq)]
+pub struct Finding {
+    pub severity: Severity,
+    pub source: String,
+    pub message: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<7} \"{}\": {}",
+            self.severity.to_string(),
+            self.source,
+            self.message
+        )
+    }
+}
+
+/// Settings that hold a file path, by source or filter kind, with the
+/// name used in findings
+const FILE_SETTINGS: &[(&str, &str, &str)] = &[
+    ("ffmpeg_source", "local_file", "media file"),
+    ("image_source", "file", "image file"),
+    ("clut_filter", "image_path", "LUT file"),
+    ("mask_filter", "image_path", "mask image"),
+    ("mask_filter_v2", "image_path", "mask image"),
+];
+
+/// Settings that hold a list of `{"value": path}` entries
+const FILE_LIST_SETTINGS: &[(&str, &str, &str)] = &[
+    ("slideshow", "files", "slideshow image"),
+    ("slideshow_v2", "files", "slideshow image"),
+    ("vlc_source", "playlist", "playlist file"),
+];
+
+/// Lints a scene collection file and prints the findings. Fails when any
+/// of them is an error.
+pub fn run(path: &Path) -> Result<()> {
+    let error = |message: String| ObsCmdError::CollectionFileError {
+        path: path.to_path_buf(),
+        message,
+    };
+    let text = std::fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+    let collection: Value = serde_json::from_str(&text).map_err(|e| error(e.to_string()))?;
+    if !collection["sources"].is_array() {
+        return Err(error(
+            "no \"sources\" list, this is not an OBS scene collection".to_string(),
+        ));
+    }
+
+    let findings = lint_collection(&collection, |path| path.exists());
+    if findings.is_empty() {
+        println!("No problems found in {}", path.display());
+        return Ok(());
+    }
+    for finding in &findings {
+        println!("{}", finding);
+    }
+
+    let errors = findings
+        .iter()
+        .filter(|finding| finding.severity == Severity::Error)
+        .count();
+    println!(
+        "\n{} error(s), {} warning(s)",
+        errors,
+        findings.len() - errors
+    );
+    if errors > 0 {
+        return Err(ObsCmdError::LintErrors { count: errors });
+    }
+    Ok(())
+}
+
+/// Checks a parsed scene collection (the JSON OBS keeps under
+/// `basic/scenes/`). `exists` tells whether a file path exists; errors
+/// come before warnings.
+pub fn lint_collection(collection: &Value, exists: impl Fn(&Path) -> bool) -> Vec<Finding> {
+    let sources: Vec<&Value> = ["sources", "groups"]
+        .iter()
+        .filter_map(|list| collection[*list].as_array())
+        .flatten()
+        .collect();
+    let mut findings = Vec::new();
+
+    for source in &sources {
+        let name = source["name"].as_str().unwrap_or_default();
+        check_files(name, source, &exists, &mut findings);
+        for filter in source["filters"].as_array().into_iter().flatten() {
+            check_files(name, filter, &exists, &mut findings);
+        }
+    }
+
+    check_unused(&sources, &mut findings);
+    check_names(&sources, &mut findings);
+    check_cycles(&sources, &mut findings);
+
+    findings.sort_by_key(|finding| finding.severity);
+    findings
+}
+
+fn is_container(source: &Value) -> bool {
+    matches!(source["id"].as_str(), Some("scene" | "group"))
+}
+
+/// Names of the sources shown by a scene or group
+fn item_sources(container: &Value) -> impl Iterator<Item = &str> {
+    container["settings"]["items"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|item| item["name"].as_str())
+}
+
+/// Checks the file paths of a source or filter
+fn check_files(
+    source: &str,
+    object: &Value,
+    exists: &impl Fn(&Path) -> bool,
+    findings: &mut Vec<Finding>,
+) {
+    let kind = object["id"].as_str().unwrap_or_default();
+    let settings = &object["settings"];
+    let missing = |what: &str, path: &str| {
+        (!path.is_empty() && !exists(Path::new(path))).then(|| Finding {
+            severity: Severity::Error,
+            source: source.to_string(),
+            message: format!("{} not found: {}", what, path),
+        })
+    };
+
+    for (file_kind, key, what) in FILE_SETTINGS {
+        // Media sources can play a URL instead of a local file
+        let local = kind != "ffmpeg_source" || settings["is_local_file"].as_bool() != Some(false);
+        if kind == *file_kind && local {
+            findings.extend(missing(what, settings[*key].as_str().unwrap_or_default()));
+        }
+    }
+    for (list_kind, key, what) in FILE_LIST_SETTINGS {
+        if kind == *list_kind {
+            for entry in settings[*key].as_array().into_iter().flatten() {
+                findings.extend(missing(what, entry["value"].as_str().unwrap_or_default()));
+            }
+        }
+    }
+
+    if kind == "browser_source" {
+        if settings["is_local_file"].as_bool() == Some(true) {
+            findings.extend(missing(
+                "browser file",
+                settings["local_file"].as_str().unwrap_or_default(),
+            ));
+        }
+        let url = settings["url"].as_str().unwrap_or_default();
+        if url.starts_with("file://") {
+            match url::Url::parse(url)
+                .ok()
+                .and_then(|url| url.to_file_path().ok())
+            {
+                Some(path) => findings.extend(missing("browser file URL", &path.to_string_lossy())),
+                None => findings.push(Finding {
+                    severity: Severity::Error,
+                    source: source.to_string(),
+                    message: format!("invalid file:// URL: {}", url),
+                }),
+            }
+        }
+    }
+}
+
+fn check_unused(sources: &[&Value], findings: &mut Vec<Finding>) {
+    let used: HashSet<&str> = sources
+        .iter()
+        .filter(|source| is_container(source))
+        .flat_map(|container| item_sources(container))
+        .collect();
+
+    for source in sources {
+        let name = source["name"].as_str().unwrap_or_default();
+        if !is_container(source) && !used.contains(name) {
+            findings.push(Finding {
+                severity: Severity::Warning,
+                source: name.to_string(),
+                message: "input is not used by any scene".to_string(),
+            });
+        }
+    }
+}
+
+/// Reports names used more than once, and names that differ only by case
+fn check_names(sources: &[&Value], findings: &mut Vec<Finding>) {
+    let mut by_folded: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    for source in sources {
+        let name = source["name"].as_str().unwrap_or_default();
+        by_folded.entry(name.to_lowercase()).or_default().push(name);
+    }
+
+    for names in by_folded.values() {
+        let distinct: Vec<&str> = names
+            .iter()
+            .copied()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        if distinct.len() < names.len() {
+            findings.push(Finding {
+                severity: Severity::Error,
+                source: names[0].to_string(),
+                message: format!("name is used by {} sources", names.len()),
+            });
+        }
+        if distinct.len() > 1 {
+            let quoted: Vec<String> = distinct
+                .iter()
+                .map(|name| format!("\"{}\"", name))
+                .collect();
+            findings.push(Finding {
+                severity: Severity::Warning,
+                source: names[0].to_string(),
+                message: format!("names differ only by case: {}", quoted.join(", ")),
+            });
+        }
+    }
+}
+
+/// Reports scenes (and groups) that end up showing themselves
+fn check_cycles(sources: &[&Value], findings: &mut Vec<Finding>) {
+    let graph: HashMap<&str, Vec<&str>> = sources
+        .iter()
+        .filter(|source| is_container(source))
+        .map(|container| {
+            (
+                container["name"].as_str().unwrap_or_default(),
+                item_sources(container).collect(),
+            )
+        })
+        .collect();
+
+    let mut names: Vec<&str> = graph.keys().copied().collect();
+    names.sort_unstable();
+    let mut done = HashSet::new();
+    let mut cycles = BTreeMap::new();
+    for name in names {
+        find_cycles(name, &graph, &mut Vec::new(), &mut done, &mut cycles);
+    }
+
+    for cycle in cycles.into_values() {
+        findings.push(Finding {
+            severity: Severity::Error,
+            source: cycle[0].to_string(),
+            message: format!("scenes are nested in a cycle: {}", cycle.join(" -> ")),
+        });
+    }
+}
+
+/// Depth-first search that records every cycle through `name` once,
+/// starting at its alphabetically first scene
+fn find_cycles<'a>(
+    name: &'a str,
+    graph: &HashMap<&'a str, Vec<&'a str>>,
+    path: &mut Vec<&'a str>,
+    done: &mut HashSet<&'a str>,
+    cycles: &mut BTreeMap<Vec<&'a str>, Vec<&'a str>>,
+) {
+    if let Some(start) = path.iter().position(|seen| *seen == name) {
+        let mut cycle = path[start..].to_vec();
+        let first = (0..cycle.len()).min_by_key(|i| cycle[*i]).unwrap_or(0);
+        cycle.rotate_left(first);
+        let mut shown = cycle.clone();
+        shown.push(cycle[0]);
+        cycles.entry(cycle).or_insert(shown);
+        return;
+    }
+    if done.contains(name) {
+        return;
+    }
+
+    path.push(name);
+    for child in graph.get(name).into_iter().flatten() {
+        if graph.contains_key(child) {
+            find_cycles(child, graph, path, done, cycles);
+        }
+    }
+    path.pop();
+    done.insert(name);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lint_collection, Severity};
+    use serde_json::json;
+    use std::path::Path;
+
+    #[test]
+    fn test_lint_collection() {
+        let collection = json!({
+            "name": "Show",
+            "sources": [
+                {"id": "scene", "name": "Main", "settings": {"items": [
+                    {"name": "Intro"}, {"name": "Logo"}, {"name": "Overlay"}, {"name": "Sub"}
+                ]}},
+                {"id": "scene", "name": "Sub", "settings": {"items": [{"name": "Main"}]}},
+                {"id": "scene", "name": "Alone", "settings": {"items": []}},
+                {"id": "ffmpeg_source", "name": "Intro",
+                 "settings": {"local_file": "/videos/missing.mp4"},
+                 "filters": [{"id": "clut_filter", "name": "LUT",
+                              "settings": {"image_path": "/luts/warm.cube"}}]},
+                {"id": "ffmpeg_source", "name": "Stream",
+                 "settings": {"is_local_file": false, "input": "rtmp://example.com/live"}},
+                {"id": "image_source", "name": "Logo", "settings": {"file": "/images/logo.png"}},
+                {"id": "image_source", "name": "logo", "settings": {"file": ""}},
+                {"id": "browser_source", "name": "Overlay",
+                 "settings": {"url": "file:///overlays/missing.html"}}
+            ],
+            "groups": []
+        });
+
+        let existing = ["/images/logo.png", "/luts/warm.cube"];
+        let findings = lint_collection(&collection, |path| {
+            existing.iter().any(|existing| Path::new(existing) == path)
+        });
+        let lines: Vec<String> = findings.iter().map(|finding| finding.to_string()).collect();
+        assert_eq!(
+            lines,
+            [
+                "error   \"Intro\": media file not found: /videos/missing.mp4",
+                "error   \"Overlay\": browser file URL not found: /overlays/missing.html",
+                "error   \"Main\": scenes are nested in a cycle: Main -> Sub -> Main",
+                "warning \"Stream\": input is not used by any scene",
+                "warning \"logo\": input is not used by any scene",
+                "warning \"Logo\": names differ only by case: \"Logo\", \"logo\"",
+            ]
+        );
+        assert_eq!(findings[0].severity, Severity::Error);
+
+        let clean = json!({"sources": [
+            {"id": "scene", "name": "Main", "settings": {"items": [{"name": "Logo"}]}},
+            {"id": "image_source", "name": "Logo", "settings": {"file": "/images/logo.png"}}
+        ]});
+        assert!(lint_collection(&clean, |_| true).is_empty());
+    }
+}
@@ -1,10 +1,19 @@
 #![allow(clippy::redundant_closure, clippy::result_large_err)]
 
+mod animation;
 mod cli;
 mod connection;
+mod diff;
 mod error;
+mod graph;
 mod handler;
 mod handlers;
+mod layout;
+mod lint;
+mod plan;
+mod rename;
+mod state;
+mod user_config;
 
 use clap::{CommandFactory, Parser};
 use cli::{Cli, ObsWebsocket};
@@ -24,9 +33,22 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
+    // Linting only reads a scene collection file
+    if let cli::Commands::Lint { file } = &cli.command {
+        return lint::run(file);
+    }
+
+    // A diff between a state file and a target, or two targets, does not
+    // use the default connection (and invalid combinations fail early)
+    if let cli::Commands::Diff(args) = &cli.command {
+        if !args.needs_default_connection() {
+            return handlers::diff::run_diff(args, None).await;
+        }
+    }
+
     let config = ConnectionConfig::default();
 
-    let client = match std::env::var("OBS_WEBSOCKET_URL") {
+    let server = match std::env::var("OBS_WEBSOCKET_URL") {
         Ok(url) => {
             let parsed_url = url::Url::parse(&url)?;
             let hostname = parsed_url
@@ -49,25 +71,29 @@ async fn main() -> Result<()> {
                     )
                 })?;
 
-            connect_with_retry(hostname, port, Some(password.to_string()), config).await?
-        }
-        Err(_) => match cli.websocket {
-            Some(ObsWebsocket {
+            ObsWebsocket {
                 hostname,
                 port,
-                password,
-            }) => connect_with_retry(hostname, port, password, config).await?,
-            None => {
-                connect_with_retry(
-                    "localhost".to_string(),
-                    4455,
-                    Some("secret".to_string()),
-                    config,
-                )
-                .await?
+                password: Some(password.to_string()),
             }
+        }
+        Err(_) => match cli.websocket {
+            Some(websocket) => websocket,
+            None => ObsWebsocket {
+                hostname: "localhost".to_string(),
+                port: 4455,
+                password: Some("secret".to_string()),
+            },
         },
     };
 
-    handle_commands(&client, &cli.command).await
+    let client = connect_with_retry(
+        server.hostname.clone(),
+        server.port,
+        server.password.clone(),
+        config,
+    )
+    .await?;
+
+    handle_commands(&client, &server, &cli.command).await
 }
@@ -0,0 +1,1019 @@
+use crate::cli::ItemBoundsType;
+use crate::error::{ObsCmdError, Result};
+use crate::state::{
+    FilterState, InputState, ItemState, ObsState, SceneState, TransitionState, VideoState,
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+
+/// A field that differs between two states. A missing value means the
+/// field is not set on that side.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Change {
+    pub field: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<Value>,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let display = |value: &Option<Value>| value.as_ref().map_or_else(none, display_value);
+        write!(
+            f,
+            "{}: {} -> {}",
+            self.field,
+            display(&self.from),
+            display(&self.to)
+        )
+    }
+}
+
+/// A scene item a plan step refers to: one that already exists in OBS, or
+/// the one created for a position of the declared item list.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ItemRef {
+    Existing(i64),
+    Created(usize),
+}
+
+/// One change to the live state, in the order it has to be applied.
+///
+/// Items are addressed by their container (a scene or a group) and either
+/// their ID or their `slot`, the position of the declared item that the
+/// step creates.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Step {
+    SetVideo {
+        video: VideoState,
+        changes: Vec<Change>,
+    },
+    /// Like `UpdateInput`, only the declared settings are sent
+    SetTransition {
+        transition: TransitionState,
+        changes: Vec<Change>,
+        kept: Vec<String>,
+    },
+    CreateScene {
+        name: String,
+    },
+    /// Creates an input together with its first item, which obs-websocket
+    /// requires
+    CreateInput {
+        input: InputState,
+        container: String,
+        slot: usize,
+        item: ItemState,
+    },
+    /// Only the declared settings are sent. `kept` lists the settings OBS
+    /// has that the state does not declare, which stay as they are
+    UpdateInput {
+        input: InputState,
+        changes: Vec<Change>,
+        kept: Vec<String>,
+    },
+    CreateItem {
+        container: String,
+        slot: usize,
+        item: ItemState,
+    },
+    UpdateItem {
+        container: String,
+        id: i64,
+        item: ItemState,
+        changes: Vec<Change>,
+    },
+    RemoveItem {
+        container: String,
+        id: i64,
+        source: String,
+    },
+    ReorderItems {
+        container: String,
+        group: bool,
+        order: Vec<ItemRef>,
+    },
+    CreateFilter {
+        source: String,
+        filter: FilterState,
+    },
+    /// Like `UpdateInput`, only the declared settings are sent
+    UpdateFilter {
+        source: String,
+        filter: FilterState,
+        changes: Vec<Change>,
+        kept: Vec<String>,
+    },
+    RemoveFilter {
+        source: String,
+        name: String,
+    },
+    ReorderFilters {
+        source: String,
+        order: Vec<String>,
+    },
+    RemoveScene {
+        name: String,
+    },
+    RemoveInput {
+        name: String,
+    },
+}
+
+impl Step {
+    /// `+` for creations, `-` for removals and `~` for everything else
+    pub fn symbol(&self) -> char {
+        match self {
+            Step::CreateScene { .. }
+            | Step::CreateInput { .. }
+            | Step::CreateItem { .. }
+            | Step::CreateFilter { .. } => '+',
+            Step::RemoveItem { .. }
+            | Step::RemoveFilter { .. }
+            | Step::RemoveScene { .. }
+            | Step::RemoveInput { .. } => '-',
+            _ => '~',
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        match self {
+            Step::SetVideo { .. } => "video settings".to_string(),
+            Step::SetTransition { transition, .. } => {
+                format!("transition \"{}\"", transition.name)
+            }
+            Step::CreateScene { name } | Step::RemoveScene { name } => {
+                format!("scene \"{}\"", name)
+            }
+            Step::CreateInput {
+                input, container, ..
+            } => format!(
+                "input \"{}\" ({}) in \"{}\"",
+                input.name, input.kind, container
+            ),
+            Step::UpdateInput { input, .. } => format!("input \"{}\"", input.name),
+            Step::RemoveInput { name } => format!("input \"{}\"", name),
+            Step::CreateItem {
+                container, item, ..
+            }
+            | Step::UpdateItem {
+                container, item, ..
+            } => format!("item \"{}\" in \"{}\"", item.source, container),
+            Step::RemoveItem {
+                container, source, ..
+            } => format!("item \"{}\" in \"{}\"", source, container),
+            Step::ReorderItems { container, .. } => format!("item order in \"{}\"", container),
+            Step::CreateFilter { source, filter } => format!(
+                "filter \"{}\" ({}) on \"{}\"",
+                filter.name, filter.kind, source
+            ),
+            Step::UpdateFilter { source, filter, .. } => {
+                format!("filter \"{}\" on \"{}\"", filter.name, source)
+            }
+            Step::RemoveFilter { source, name } => {
+                format!("filter \"{}\" on \"{}\"", name, source)
+            }
+            Step::ReorderFilters { source, .. } => format!("filter order on \"{}\"", source),
+        }
+    }
+
+    pub fn changes(&self) -> &[Change] {
+        match self {
+            Step::SetVideo { changes, .. }
+            | Step::SetTransition { changes, .. }
+            | Step::UpdateInput { changes, .. }
+            | Step::UpdateItem { changes, .. }
+            | Step::UpdateFilter { changes, .. } => changes,
+            _ => &[],
+        }
+    }
+
+    /// Settings that are left alone because the state does not declare them
+    pub fn kept(&self) -> &[String] {
+        match self {
+            Step::SetTransition { kept, .. }
+            | Step::UpdateInput { kept, .. }
+            | Step::UpdateFilter { kept, .. } => kept,
+            _ => &[],
+        }
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "  {} {}", self.symbol(), self.summary())?;
+        for change in self.changes() {
+            write!(f, "\n      {}", change)?;
+        }
+        for field in self.kept() {
+            write!(f, "\n      {}: kept, not declared", field)?;
+        }
+        Ok(())
+    }
+}
+
+/// Computes the steps that turn the live state into the desired one.
+///
+/// Scenes, inputs, items and filters the desired state does not mention are
+/// left alone unless `prune` is set. Special inputs (Desktop Audio, Mic/Aux)
+/// are never removed. Items are matched by source name in list order, and
+/// filters by name.
+pub fn plan(
+    desired: &ObsState,
+    live: &ObsState,
+    prune: bool,
+    special_inputs: &[String],
+) -> Result<Vec<Step>> {
+    check_names(desired)?;
+    if prune && desired.scenes.is_empty() {
+        return Err(plan_error(
+            "--prune needs at least one declared scene, OBS cannot remove its last scene",
+        ));
+    }
+
+    let live_scenes: HashMap<&str, &SceneState> = live
+        .scenes
+        .iter()
+        .map(|scene| (scene.name.as_str(), scene))
+        .collect();
+    let live_inputs: HashMap<&str, &InputState> = live
+        .inputs
+        .iter()
+        .map(|input| (input.name.as_str(), input))
+        .collect();
+
+    // Sources the declared items can show once the plan has been applied
+    let mut sources: HashSet<&str> = desired
+        .scenes
+        .iter()
+        .map(|scene| scene.name.as_str())
+        .chain(desired.inputs.iter().map(|input| input.name.as_str()))
+        .chain(special_inputs.iter().map(String::as_str))
+        .collect();
+    if !prune {
+        sources.extend(live_scenes.keys());
+        sources.extend(live_inputs.keys());
+    }
+
+    // New inputs are created in the first declared scene that shows them
+    let mut input_homes = HashMap::new();
+    for input in &desired.inputs {
+        if live_inputs.contains_key(input.name.as_str()) {
+            continue;
+        }
+        if live_scenes.contains_key(input.name.as_str()) {
+            return Err(plan_error(format!(
+                "Input '{}' cannot be created because a scene already has that name",
+                input.name
+            )));
+        }
+        let home = desired.scenes.iter().find_map(|scene| {
+            scene
+                .items
+                .iter()
+                .position(|item| item.source == input.name)
+                .map(|slot| (scene.name.as_str(), slot, &scene.items[slot]))
+        });
+        match home {
+            Some(home) => {
+                input_homes.insert(input.name.as_str(), home);
+            }
+            None => {
+                return Err(plan_error(format!(
+                    "Input '{}' is not shown directly in any declared scene. \
+                     obs-websocket can only create inputs inside a scene",
+                    input.name
+                )))
+            }
+        }
+    }
+
+    let mut planner = Planner {
+        prune,
+        sources,
+        input_homes,
+        steps: Vec::new(),
+    };
+
+    if let (Some(want), Some(have)) = (&desired.video, &live.video) {
+        let mut changes = Vec::new();
+        value_changes("", &json(want), &json(have), &mut changes);
+        if !changes.is_empty() {
+            planner.steps.push(Step::SetVideo {
+                video: want.clone(),
+                changes,
+            });
+        }
+    }
+    if let Some(want) = &desired.transition {
+        planner.plan_transition(want, live.transition.as_ref());
+    }
+
+    for scene in &desired.scenes {
+        if live_scenes.contains_key(scene.name.as_str()) {
+            continue;
+        }
+        if live_inputs.contains_key(scene.name.as_str()) {
+            return Err(plan_error(format!(
+                "Scene '{}' cannot be created because an input already has that name",
+                scene.name
+            )));
+        }
+        planner.steps.push(Step::CreateScene {
+            name: scene.name.clone(),
+        });
+    }
+
+    for input in &desired.inputs {
+        planner.plan_input(input, live_inputs.get(input.name.as_str()).copied())?;
+    }
+
+    for scene in &desired.scenes {
+        let live_items = live_scenes
+            .get(scene.name.as_str())
+            .map(|live| live.items.as_slice())
+            .unwrap_or_default();
+        planner.plan_items(&scene.name, false, &scene.items, live_items)?;
+    }
+
+    for scene in &desired.scenes {
+        let live_filters = live_scenes
+            .get(scene.name.as_str())
+            .map(|live| live.filters.as_slice())
+            .unwrap_or_default();
+        planner.plan_filters(&scene.name, &scene.filters, live_filters);
+    }
+    for input in &desired.inputs {
+        let live_filters = live_inputs
+            .get(input.name.as_str())
+            .map(|live| live.filters.as_slice())
+            .unwrap_or_default();
+        planner.plan_filters(&input.name, &input.filters, live_filters);
+    }
+
+    if prune {
+        let declared_scenes: HashSet<&str> = desired
+            .scenes
+            .iter()
+            .map(|scene| scene.name.as_str())
+            .collect();
+        let declared_inputs: HashSet<&str> = desired
+            .inputs
+            .iter()
+            .map(|input| input.name.as_str())
+            .collect();
+        for scene in &live.scenes {
+            if !declared_scenes.contains(scene.name.as_str()) {
+                planner.steps.push(Step::RemoveScene {
+                    name: scene.name.clone(),
+                });
+            }
+        }
+        for input in &live.inputs {
+            if !declared_inputs.contains(input.name.as_str())
+                && !special_inputs.contains(&input.name)
+            {
+                planner.steps.push(Step::RemoveInput {
+                    name: input.name.clone(),
+                });
+            }
+        }
+    }
+
+    Ok(planner.steps)
+}
+
+/// Number of steps that create, update and remove something
+pub fn count_steps(steps: &[Step]) -> (usize, usize, usize) {
+    let count = |symbol| steps.iter().filter(|step| step.symbol() == symbol).count();
+    (count('+'), count('~'), count('-'))
+}
+
+struct Planner<'a> {
+    prune: bool,
+    sources: HashSet<&'a str>,
+    /// Scene, item slot and item each new input is created with
+    input_homes: HashMap<&'a str, (&'a str, usize, &'a ItemState)>,
+    steps: Vec<Step>,
+}
+
+impl<'a> Planner<'a> {
+    fn plan_transition(&mut self, want: &TransitionState, have: Option<&TransitionState>) {
+        let mut changes = Vec::new();
+        let same = have.is_some_and(|have| have.name == want.name);
+        if !same {
+            changes.push(Change {
+                field: "name".to_string(),
+                from: have.map(|have| Value::from(have.name.as_str())),
+                to: Some(Value::from(want.name.as_str())),
+            });
+        }
+        if let Some(duration) = want.duration_ms {
+            let current = have.and_then(|have| have.duration_ms);
+            if current != Some(duration) {
+                changes.push(Change {
+                    field: "duration_ms".to_string(),
+                    from: current.map(Value::from),
+                    to: Some(Value::from(duration)),
+                });
+            }
+        }
+        let mut kept = Vec::new();
+        if let Some(settings) = &want.settings {
+            // Settings of another transition tell nothing about this one
+            let current = have
+                .filter(|_| same)
+                .and_then(|have| have.settings.clone())
+                .unwrap_or_else(|| Value::Object(Default::default()));
+            kept = settings_changes(settings, &current, &mut changes);
+        }
+
+        if !changes.is_empty() {
+            self.steps.push(Step::SetTransition {
+                transition: want.clone(),
+                changes,
+                kept,
+            });
+        }
+    }
+
+    fn plan_input(&mut self, want: &InputState, have: Option<&InputState>) -> Result<()> {
+        let Some(have) = have else {
+            let (scene, slot, item) = self.input_homes[want.name.as_str()];
+            self.steps.push(Step::CreateInput {
+                input: want.clone(),
+                container: scene.to_string(),
+                slot,
+                item: item.clone(),
+            });
+            return Ok(());
+        };
+
+        if have.kind != want.kind {
+            return Err(plan_error(format!(
+                "Input '{}' is a {} in OBS but declared as {}. Remove or rename it first",
+                want.name, have.kind, want.kind
+            )));
+        }
+
+        let mut changes = Vec::new();
+        let kept = settings_changes(&want.settings, &have.settings, &mut changes);
+        if let Some(audio) = &want.audio {
+            let Some(current) = &have.audio else {
+                return Err(plan_error(format!(
+                    "Input '{}' has no audio, remove its audio section",
+                    want.name
+                )));
+            };
+            value_changes("audio", &json(audio), &json(current), &mut changes);
+        }
+        if !changes.is_empty() {
+            self.steps.push(Step::UpdateInput {
+                input: want.clone(),
+                changes,
+                kept,
+            });
+        }
+        Ok(())
+    }
+
+    fn plan_items(
+        &mut self,
+        container: &str,
+        group: bool,
+        desired: &[ItemState],
+        live: &[ItemState],
+    ) -> Result<()> {
+        let mut taken = vec![false; live.len()];
+        let mut order = Vec::with_capacity(desired.len());
+        // Slots in the order their items are created; each lands on top
+        let mut created = Vec::new();
+
+        for (slot, want) in desired.iter().enumerate() {
+            let matched = (0..live.len()).find(|&i| !taken[i] && live[i].source == want.source);
+            let is_group = matched.is_some_and(|i| live[i].kind.as_deref() == Some("group"));
+            if matched.is_none()
+                && (!want.items.is_empty() || want.kind.as_deref() == Some("group"))
+            {
+                return Err(plan_error(format!(
+                    "Group '{}' does not exist in '{}' and obs-websocket cannot create groups",
+                    want.source, container
+                )));
+            }
+            if !is_group && !self.sources.contains(want.source.as_str()) {
+                return Err(plan_error(if self.prune {
+                    format!(
+                        "'{}' in '{}' is neither a declared scene nor a declared input, \
+                         so --prune would remove it",
+                        want.source, container
+                    )
+                } else {
+                    format!(
+                        "'{}' in '{}' is neither a scene nor an input",
+                        want.source, container
+                    )
+                }));
+            }
+
+            let Some(i) = matched else {
+                let home = self.input_homes.get(want.source.as_str());
+                if !group && home.is_some_and(|home| home.0 == container && home.1 == slot) {
+                    // Created together with its input, before any other item
+                    created.insert(0, slot);
+                } else {
+                    self.steps.push(Step::CreateItem {
+                        container: container.to_string(),
+                        slot,
+                        item: want.clone(),
+                    });
+                    created.push(slot);
+                }
+                order.push(ItemRef::Created(slot));
+                continue;
+            };
+
+            taken[i] = true;
+            let have = &live[i];
+            let id = have.id.unwrap_or_default();
+            let changes = item_changes(want, have);
+            if !changes.is_empty() {
+                self.steps.push(Step::UpdateItem {
+                    container: container.to_string(),
+                    id,
+                    item: want.clone(),
+                    changes,
+                });
+            }
+            if is_group {
+                self.plan_items(&have.source, true, &want.items, &have.items)?;
+            } else if !want.items.is_empty() {
+                return Err(plan_error(format!(
+                    "'{}' in '{}' is not a group, so it cannot list items",
+                    want.source, container
+                )));
+            }
+            order.push(ItemRef::Existing(id));
+        }
+
+        let mut remaining = Vec::new();
+        for (i, have) in live.iter().enumerate() {
+            let id = have.id.unwrap_or_default();
+            if taken[i] {
+                remaining.push(ItemRef::Existing(id));
+            } else if self.prune {
+                self.steps.push(Step::RemoveItem {
+                    container: container.to_string(),
+                    id,
+                    source: have.source.clone(),
+                });
+            }
+        }
+
+        // Without reordering, new items end up above the existing ones
+        let predicted: Vec<ItemRef> = created
+            .iter()
+            .rev()
+            .map(|slot| ItemRef::Created(*slot))
+            .chain(remaining)
+            .collect();
+        if predicted != order {
+            self.steps.push(Step::ReorderItems {
+                container: container.to_string(),
+                group,
+                order,
+            });
+        }
+        Ok(())
+    }
+
+    fn plan_filters(&mut self, source: &str, desired: &[FilterState], live: &[FilterState]) {
+        let declared = |name: &str| desired.iter().find(|filter| filter.name == name);
+
+        // Filters that stay in place, in chain order
+        let mut kept = Vec::new();
+        for have in live {
+            match declared(&have.name) {
+                Some(want) if want.kind == have.kind => kept.push(have.name.as_str()),
+                // A filter cannot change its kind, so it is replaced
+                Some(_) => self.steps.push(Step::RemoveFilter {
+                    source: source.to_string(),
+                    name: have.name.clone(),
+                }),
+                None if self.prune => self.steps.push(Step::RemoveFilter {
+                    source: source.to_string(),
+                    name: have.name.clone(),
+                }),
+                None => {}
+            }
+        }
+
+        let mut created = Vec::new();
+        for want in desired {
+            match live.iter().find(|have| have.name == want.name) {
+                Some(have) if have.kind == want.kind => {
+                    let mut changes = Vec::new();
+                    value_changes(
+                        "enabled",
+                        &Value::Bool(want.enabled),
+                        &Value::Bool(have.enabled),
+                        &mut changes,
+                    );
+                    let kept = settings_changes(&want.settings, &have.settings, &mut changes);
+                    if !changes.is_empty() {
+                        self.steps.push(Step::UpdateFilter {
+                            source: source.to_string(),
+                            filter: want.clone(),
+                            changes,
+                            kept,
+                        });
+                    }
+                }
+                _ => {
+                    self.steps.push(Step::CreateFilter {
+                        source: source.to_string(),
+                        filter: want.clone(),
+                    });
+                    created.push(want.name.as_str());
+                }
+            }
+        }
+
+        // New filters are appended to the end of the chain
+        let predicted: Vec<&str> = kept
+            .into_iter()
+            .filter(|name| declared(name).is_some())
+            .chain(created)
+            .collect();
+        let order: Vec<&str> = desired.iter().map(|filter| filter.name.as_str()).collect();
+        if predicted != order {
+            self.steps.push(Step::ReorderFilters {
+                source: source.to_string(),
+                order: order.into_iter().map(String::from).collect(),
+            });
+        }
+    }
+}
+
+/// Scene and input names share one namespace in OBS
+fn check_names(state: &ObsState) -> Result<()> {
+    let mut names = HashSet::new();
+    let all = state
+        .scenes
+        .iter()
+        .map(|scene| &scene.name)
+        .chain(state.inputs.iter().map(|input| &input.name));
+    for name in all {
+        if !names.insert(name.as_str()) {
+            return Err(plan_error(format!(
+                "'{}' is declared more than once. Scenes and inputs need unique names",
+                name
+            )));
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn item_changes(want: &ItemState, have: &ItemState) -> Vec<Change> {
+    let mut changes = Vec::new();
+    value_changes(
+        "enabled",
+        &Value::Bool(want.enabled),
+        &Value::Bool(have.enabled),
+        &mut changes,
+    );
+    value_changes(
+        "locked",
+        &Value::Bool(want.locked),
+        &Value::Bool(have.locked),
+        &mut changes,
+    );
+    value_changes(
+        "blend_mode",
+        &json(&want.blend_mode),
+        &json(&have.blend_mode),
+        &mut changes,
+    );
+
+    let mut wanted = json(&want.transform);
+    let mut current = json(&have.transform);
+    // OBS keeps the bounds of an item without a bounding box around
+    if want.transform.bounds_type == ItemBoundsType::None {
+        for transform in [&mut wanted, &mut current] {
+            if let Value::Object(fields) = transform {
+                for field in ["bounds_alignment", "bounds_width", "bounds_height"] {
+                    fields.remove(field);
+                }
+            }
+        }
+    }
+    value_changes("transform", &wanted, &current, &mut changes);
+    changes
+}
+
+/// Collects the fields that differ between two values, descending into
+/// objects. `path` is the field name of the values.
+pub(crate) fn value_changes(path: &str, want: &Value, have: &Value, changes: &mut Vec<Change>) {
+    if let (Value::Object(wanted), Value::Object(current)) = (want, have) {
+        let keys: BTreeSet<&String> = wanted.keys().chain(current.keys()).collect();
+        for key in keys {
+            let field = if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", path, key)
+            };
+            match (wanted.get(key), current.get(key)) {
+                (Some(want), Some(have)) => value_changes(&field, want, have, changes),
+                (want, have) => changes.push(Change {
+                    field,
+                    from: have.cloned(),
+                    to: want.cloned(),
+                }),
+            }
+        }
+    } else if !values_equal(want, have) {
+        changes.push(Change {
+            field: path.to_string(),
+            from: Some(have.clone()),
+            to: Some(want.clone()),
+        });
+    }
+}
+
+/// Collects the changes of the declared settings, which are sent as an
+/// overlay, and returns the settings only OBS has. A declared setting
+/// replaces the current value as a whole, nested objects included.
+fn settings_changes(want: &Value, have: &Value, changes: &mut Vec<Change>) -> Vec<String> {
+    let (Value::Object(wanted), Value::Object(current)) = (want, have) else {
+        value_changes("settings", want, have, changes);
+        return Vec::new();
+    };
+    for (key, value) in wanted {
+        let field = format!("settings.{}", key);
+        match current.get(key) {
+            Some(have) => value_changes(&field, value, have, changes),
+            None => changes.push(Change {
+                field,
+                from: None,
+                to: Some(value.clone()),
+            }),
+        }
+    }
+    current
+        .keys()
+        .filter(|key| !wanted.contains_key(*key))
+        .map(|key| format!("settings.{}", key))
+        .collect()
+}
+
+/// Compares values like `==`, but ignores the rounding noise of floats
+/// that went through OBS
+fn values_equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) if x.is_f64() || y.is_f64() => {
+            let (x, y) = (x.as_f64().unwrap_or(0.0), y.as_f64().unwrap_or(0.0));
+            (x - y).abs() <= 1e-3_f64.max(x.abs().max(y.abs()) * 1e-6)
+        }
+        (Value::Array(x), Value::Array(y)) => {
+            x.len() == y.len() && x.iter().zip(y).all(|(x, y)| values_equal(x, y))
+        }
+        (Value::Object(x), Value::Object(y)) => {
+            x.len() == y.len()
+                && x.iter()
+                    .all(|(key, x)| y.get(key).is_some_and(|y| values_equal(x, y)))
+        }
+        _ => a == b,
+    }
+}
+
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::Number(number) if number.is_f64() => {
+            let rounded = (number.as_f64().unwrap_or(0.0) * 1000.0).round() / 1000.0;
+            rounded.to_string()
+        }
+        _ => value.to_string(),
+    }
+}
+
+pub(crate) fn json<T: Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+fn none() -> String {
+    "(none)".to_string()
+}
+
+fn plan_error(message: impl Into<String>) -> ObsCmdError {
+    ObsCmdError::InvalidStatePlan {
+        message: message.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{count_steps, plan, ItemRef, Step};
+    use crate::state::{ObsState, TransitionState};
+    use serde_json::json;
+
+    fn state(value: serde_json::Value) -> ObsState {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn live() -> ObsState {
+        state(json!({
+            "version": 1,
+            "scenes": [
+                {"name": "Live", "items": [
+                    {"source": "Overlay", "id": 3, "kind": "image_source"},
+                    {"source": "Camera", "id": 1, "kind": "v4l2_input",
+                     "transform": {"position_x": 10.000001}}
+                ]},
+                {"name": "Old", "items": []}
+            ],
+            "inputs": [
+                {"name": "Camera", "kind": "v4l2_input",
+                 "settings": {"device": "/dev/video0", "resolution": 1},
+                 "filters": [
+                     {"name": "Color", "kind": "color_filter_v2", "settings": {"opacity": 1.0}},
+                     {"name": "Crop", "kind": "crop_filter"}
+                 ]},
+                {"name": "Overlay", "kind": "image_source"},
+                {"name": "Desktop Audio", "kind": "pulse_output_capture",
+                 "audio": {"volume_mul": 0.5}}
+            ]
+        }))
+    }
+
+    #[test]
+    fn test_plan_without_changes() {
+        let live = live();
+        let steps = plan(&live, &live, true, &["Desktop Audio".to_string()]).unwrap();
+        assert!(steps.is_empty(), "{:?}", steps);
+    }
+
+    #[test]
+    fn test_plan_changes() {
+        let desired = state(json!({
+            "version": 1,
+            "scenes": [
+                {"name": "Live", "items": [
+                    {"source": "Guest"},
+                    {"source": "Camera", "transform": {"position_x": 10.0, "scale_x": 0.5}},
+                    {"source": "Overlay", "enabled": false}
+                ]}
+            ],
+            "inputs": [
+                {"name": "Camera", "kind": "v4l2_input", "settings": {"device": "/dev/video2"},
+                 "filters": [
+                     {"name": "Crop", "kind": "crop_filter"},
+                     {"name": "Color", "kind": "color_filter_v2", "enabled": false,
+                      "settings": {"opacity": 1}}
+                 ]},
+                {"name": "Overlay", "kind": "image_source"},
+                {"name": "Guest", "kind": "browser_source"},
+                {"name": "Desktop Audio", "kind": "pulse_output_capture",
+                 "audio": {"volume_mul": 0.25}}
+            ]
+        }));
+
+        let steps = plan(&desired, &live(), false, &[]).unwrap();
+        let summaries: Vec<String> = steps
+            .iter()
+            .map(|step| format!("{} {}", step.symbol(), step.summary()))
+            .collect();
+        assert_eq!(
+            summaries,
+            [
+                "~ input \"Camera\"",
+                "+ input \"Guest\" (browser_source) in \"Live\"",
+                "~ input \"Desktop Audio\"",
+                "~ item \"Camera\" in \"Live\"",
+                "~ item \"Overlay\" in \"Live\"",
+                "~ item order in \"Live\"",
+                "~ filter \"Color\" on \"Camera\"",
+                "~ filter order on \"Camera\"",
+            ]
+        );
+        assert_eq!(count_steps(&steps), (1, 7, 0));
+
+        // Only the fields that differ are listed; 10.000001 is 10
+        assert_eq!(
+            steps[3].to_string(),
+            "  ~ item \"Camera\" in \"Live\"\n      transform.scale_x: 1 -> 0.5"
+        );
+        assert_eq!(
+            steps[0].to_string(),
+            "  ~ input \"Camera\"\n      settings.device: \"/dev/video0\" -> \"/dev/video2\"\n      \
+             settings.resolution: kept, not declared"
+        );
+        assert_eq!(steps[6].changes().len(), 1);
+        match &steps[5] {
+            Step::ReorderItems { order, .. } => assert_eq!(
+                order,
+                &[
+                    ItemRef::Created(0),
+                    ItemRef::Existing(1),
+                    ItemRef::Existing(3)
+                ]
+            ),
+            other => panic!("unexpected step {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_plan_transition_settings() {
+        let mut live = live();
+        live.transition = Some(TransitionState {
+            name: "Swipe".to_string(),
+            kind: Some("swipe_transition".to_string()),
+            duration_ms: Some(300),
+            settings: Some(json!({"direction": "left", "swipe_in": false})),
+        });
+        let mut desired = live.clone();
+        desired.transition = Some(TransitionState {
+            name: "Swipe".to_string(),
+            kind: None,
+            duration_ms: None,
+            settings: Some(json!({"direction": "left"})),
+        });
+        // Undeclared settings are not changes
+        assert!(plan(&desired, &live, false, &[]).unwrap().is_empty());
+
+        desired.transition.as_mut().unwrap().settings = Some(json!({"direction": "up"}));
+        let steps = plan(&desired, &live, false, &[]).unwrap();
+        assert_eq!(
+            steps[0].to_string(),
+            "  ~ transition \"Swipe\"\n      settings.direction: \"left\" -> \"up\"\n      \
+             settings.swipe_in: kept, not declared"
+        );
+    }
+
+    #[test]
+    fn test_plan_prune() {
+        let desired = state(json!({
+            "version": 1,
+            "scenes": [
+                {"name": "Live", "items": [
+                    {"source": "Overlay", "id": 3},
+                    {"source": "Intro"}
+                ]},
+                {"name": "Intro"}
+            ],
+            "inputs": [{"name": "Overlay", "kind": "image_source"}]
+        }));
+
+        let steps = plan(&desired, &live(), true, &["Desktop Audio".to_string()]).unwrap();
+        let summaries: Vec<String> = steps
+            .iter()
+            .map(|step| format!("{} {}", step.symbol(), step.summary()))
+            .collect();
+        assert_eq!(
+            summaries,
+            [
+                "+ scene \"Intro\"",
+                "+ item \"Intro\" in \"Live\"",
+                "- item \"Camera\" in \"Live\"",
+                "~ item order in \"Live\"",
+                "- scene \"Old\"",
+                "- input \"Camera\"",
+            ]
+        );
+
+        // Without --prune the undeclared scene, input and item stay
+        let steps = plan(&desired, &live(), false, &[]).unwrap();
+        assert_eq!(count_steps(&steps), (2, 1, 0));
+    }
+
+    #[test]
+    fn test_plan_errors() {
+        let unplaced = state(json!({
+            "version": 1,
+            "inputs": [{"name": "Guest", "kind": "browser_source"}]
+        }));
+        assert!(plan(&unplaced, &live(), false, &[]).is_err());
+
+        let new_group = state(json!({
+            "version": 1,
+            "scenes": [{"name": "Live", "items": [
+                {"source": "Cams", "items": [{"source": "Camera"}]}
+            ]}]
+        }));
+        assert!(plan(&new_group, &live(), false, &[]).is_err());
+
+        let duplicate = state(json!({
+            "version": 1,
+            "scenes": [{"name": "Camera"}],
+            "inputs": [{"name": "Camera", "kind": "v4l2_input"}]
+        }));
+        assert!(plan(&duplicate, &live(), false, &[]).is_err());
+
+        let unknown = state(json!({
+            "version": 1,
+            "scenes": [{"name": "Live", "items": [{"source": "Missing"}]}]
+        }));
+        assert!(plan(&unknown, &live(), false, &[]).is_err());
+    }
+}
@@ -0,0 +1,333 @@
+use crate::error::{ObsCmdError, Result};
+use regex::{Regex, RegexBuilder};
+use std::collections::HashSet;
+use std::fmt;
+
+/// A sed-style substitution: `s/pattern/replacement/flags`.
+///
+/// Any character can stand in for `/`; write it as `\/` inside the pattern
+/// or replacement. Groups are referenced as `$1` or `\1`. Flags are `g`
+/// (replace every match, not just the first) and `i` (ignore case).
+#[derive(Debug)]
+pub struct Substitution {
+    regex: Regex,
+    replacement: String,
+    global: bool,
+}
+
+impl Substitution {
+    pub fn parse(expression: &str) -> Result<Self> {
+        let error = |message: &str| ObsCmdError::InvalidRenameExpression {
+            expression: expression.to_string(),
+            message: message.to_string(),
+        };
+        let mut chars = expression.chars();
+        if chars.next() != Some('s') {
+            return Err(error("expected s/pattern/replacement/"));
+        }
+        let delimiter = chars
+            .next()
+            .filter(|c| !c.is_alphanumeric() && !c.is_whitespace() && *c != '\\')
+            .ok_or_else(|| error("expected a delimiter such as / after s"))?;
+
+        let mut parts = Vec::new();
+        let mut part = String::new();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some(next) if next == delimiter => part.push(next),
+                    Some(next) => {
+                        part.push('\\');
+                        part.push(next);
+                    }
+                    None => part.push('\\'),
+                },
+                c if c == delimiter => parts.push(std::mem::take(&mut part)),
+                c => part.push(c),
+            }
+        }
+        parts.push(part);
+        let [pattern, replacement, flags] = <[String; 3]>::try_from(parts)
+            .map_err(|_| error("expected s/pattern/replacement/ with three delimiters"))?;
+
+        let mut global = false;
+        let mut ignore_case = false;
+        for flag in flags.chars() {
+            match flag {
+                'g' => global = true,
+                'i' => ignore_case = true,
+                _ => return Err(error(&format!("unknown flag '{}', use g or i", flag))),
+            }
+        }
+
+        Ok(Self {
+            regex: RegexBuilder::new(&pattern)
+                .case_insensitive(ignore_case)
+                .build()?,
+            replacement: sed_groups(&replacement),
+            global,
+        })
+    }
+
+    /// The new name, when the pattern matches and changes the name
+    pub fn apply(&self, name: &str) -> Option<String> {
+        if !self.regex.is_match(name) {
+            return None;
+        }
+        let renamed = if self.global {
+            self.regex.replace_all(name, self.replacement.as_str())
+        } else {
+            self.regex.replace(name, self.replacement.as_str())
+        };
+        (renamed != name).then(|| renamed.into_owned())
+    }
+}
+
+/// Turns `\1` and bare `$1` group references into the `${1}` the regex
+/// crate uses. Left alone, `$1a` would refer to a group named `1a`
+fn sed_groups(replacement: &str) -> String {
+    let mut converted = String::with_capacity(replacement.len());
+    let mut chars = replacement.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('\\', Some(digit)) if digit.is_ascii_digit() => {
+                converted.push_str(&format!("${{{}}}", digit));
+                chars.next();
+            }
+            ('$', Some(digit)) if digit.is_ascii_digit() => {
+                let mut group = String::new();
+                while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                    group.push(digit);
+                }
+                converted.push_str(&format!("${{{}}}", group));
+            }
+            ('$', Some('$')) => {
+                converted.push_str("$$");
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                converted.push('\\');
+                chars.next();
+            }
+            _ => converted.push(c),
+        }
+    }
+    converted
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SourceType {
+    Input,
+    Scene,
+}
+
+impl fmt::Display for SourceType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SourceType::Input => "input",
+            SourceType::Scene => "scene",
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rename {
+    pub source_type: SourceType,
+    pub from: String,
+    pub to: String,
+}
+
+/// Renames the `candidates` the substitution changes. `existing` holds the
+/// name of every source, as inputs, scenes and groups share one namespace
+/// in OBS. Fails, listing every clash, when a new name is empty, is given
+/// twice or is kept by a source that is not renamed.
+pub fn plan_renames(
+    substitution: &Substitution,
+    candidates: &[(SourceType, String)],
+    existing: &[String],
+) -> Result<Vec<Rename>> {
+    let renames: Vec<Rename> = candidates
+        .iter()
+        .filter_map(|(source_type, name)| {
+            substitution.apply(name).map(|to| Rename {
+                source_type: *source_type,
+                from: name.clone(),
+                to,
+            })
+        })
+        .collect();
+
+    let renamed: HashSet<&str> = renames.iter().map(|rename| rename.from.as_str()).collect();
+    let mut clashes = Vec::new();
+    for (i, rename) in renames.iter().enumerate() {
+        if rename.to.trim().is_empty() {
+            clashes.push(format!("\"{}\" would get an empty name", rename.from));
+        } else if let Some(other) = renames[..i].iter().find(|other| other.to == rename.to) {
+            clashes.push(format!(
+                "\"{}\" and \"{}\" would both be named \"{}\"",
+                other.from, rename.from, rename.to
+            ));
+        } else if existing.contains(&rename.to) && !renamed.contains(rename.to.as_str()) {
+            clashes.push(format!(
+                "\"{}\" would be named \"{}\", which another source already uses",
+                rename.from, rename.to
+            ));
+        }
+    }
+    if !clashes.is_empty() {
+        return Err(ObsCmdError::RenameClashes {
+            clashes: clashes.join("\n  "),
+        });
+    }
+    Ok(renames)
+}
+
+/// Orders renames so no step reuses a name that is still taken: a source
+/// is renamed only after the source holding its new name has moved on.
+/// Renames that swap names go through a temporary name.
+pub fn order_renames(renames: &[Rename], existing: &[String]) -> Vec<Rename> {
+    let mut taken: HashSet<String> = existing.iter().cloned().collect();
+    let mut pending = renames.to_vec();
+    let mut steps = Vec::with_capacity(pending.len());
+
+    while !pending.is_empty() {
+        if let Some(i) = pending
+            .iter()
+            .position(|rename| !taken.contains(&rename.to))
+        {
+            let rename = pending.remove(i);
+            taken.remove(&rename.from);
+            taken.insert(rename.to.clone());
+            steps.push(rename);
+            continue;
+        }
+
+        // Every new name is held by another pending rename: a cycle
+        let rename = &mut pending[0];
+        let mut temporary = format!("{} (renaming)", rename.from);
+        let mut n = 2;
+        while taken.contains(&temporary) {
+            temporary = format!("{} (renaming {})", rename.from, n);
+            n += 1;
+        }
+        taken.remove(&rename.from);
+        taken.insert(temporary.clone());
+        steps.push(Rename {
+            source_type: rename.source_type,
+            from: std::mem::replace(&mut rename.from, temporary.clone()),
+            to: temporary,
+        });
+    }
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{order_renames, plan_renames, Rename, SourceType, Substitution};
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn pairs(renames: &[Rename]) -> Vec<(&str, &str)> {
+        renames
+            .iter()
+            .map(|rename| (rename.from.as_str(), rename.to.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_substitution() {
+        let sub = Substitution::parse(r"s/^Cam (\d)/Camera-$1/").unwrap();
+        assert_eq!(sub.apply("Cam 2"), Some("Camera-2".to_string()));
+        assert_eq!(sub.apply("Cam 2 Wide"), Some("Camera-2 Wide".to_string()));
+        assert_eq!(sub.apply("Webcam 2"), None);
+
+        // A group reference followed by text
+        let sub = Substitution::parse(r"s/(\d)/$1a/").unwrap();
+        assert_eq!(sub.apply("5"), Some("5a".to_string()));
+        let sub = Substitution::parse(r"s/(\d)/\1a/").unwrap();
+        assert_eq!(sub.apply("5"), Some("5a".to_string()));
+        let sub = Substitution::parse(r"s/(\d)/$$1/").unwrap();
+        assert_eq!(sub.apply("5"), Some("$1".to_string()));
+
+        let sub = Substitution::parse("s|a/b|x|gi").unwrap();
+        assert_eq!(sub.apply("A/B a/b"), Some("x x".to_string()));
+        let sub = Substitution::parse(r"s/(\w+) \/ (\w+)/\2 \/ \1/").unwrap();
+        assert_eq!(sub.apply("Left / Right"), Some("Right / Left".to_string()));
+        assert_eq!(
+            Substitution::parse("s/Cam/Cam/").unwrap().apply("Cam"),
+            None
+        );
+
+        for invalid in [
+            "Cam/Camera",
+            "s/Cam/Camera",
+            "s/Cam/Camera/x",
+            "s/(/x/",
+            "sa",
+        ] {
+            assert!(Substitution::parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_plan_and_order_renames() {
+        let existing = names(&["Cam 1", "Cam 2", "Camera-3", "Live", "Old Live"]);
+        let inputs = |list: &[&str]| -> Vec<(SourceType, String)> {
+            list.iter()
+                .map(|name| (SourceType::Input, name.to_string()))
+                .collect()
+        };
+
+        // "Cam 3" -> "Camera-3" clashes with an input that keeps its name
+        let sub = Substitution::parse(r"s/^Cam (\d)/Camera-$1/").unwrap();
+        let err = plan_renames(&sub, &inputs(&["Cam 1", "Cam 3"]), &existing).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("\"Cam 3\" would be named \"Camera-3\""));
+
+        let sub = Substitution::parse("s/ [0-9]$//").unwrap();
+        let err = plan_renames(&sub, &inputs(&["Cam 1", "Cam 2"]), &existing).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("\"Cam 1\" and \"Cam 2\" would both be named \"Cam\""));
+
+        // A chain is applied back to front, a swap through a temporary name
+        let sub = Substitution::parse("s/^(Old )?Live$/Old $0/").unwrap();
+        let scenes = [
+            (SourceType::Scene, "Live".to_string()),
+            (SourceType::Scene, "Old Live".to_string()),
+        ];
+        let renames = plan_renames(&sub, &scenes, &existing).unwrap();
+        assert_eq!(
+            pairs(&renames),
+            [("Live", "Old Live"), ("Old Live", "Old Old Live")]
+        );
+        assert_eq!(
+            pairs(&order_renames(&renames, &existing)),
+            [("Old Live", "Old Old Live"), ("Live", "Old Live")]
+        );
+
+        let swap = [
+            Rename {
+                source_type: SourceType::Input,
+                from: "Cam 1".to_string(),
+                to: "Cam 2".to_string(),
+            },
+            Rename {
+                source_type: SourceType::Input,
+                from: "Cam 2".to_string(),
+                to: "Cam 1".to_string(),
+            },
+        ];
+        assert_eq!(
+            pairs(&order_renames(&swap, &existing)),
+            [
+                ("Cam 1", "Cam 1 (renaming)"),
+                ("Cam 2", "Cam 1"),
+                ("Cam 1 (renaming)", "Cam 2")
+            ]
+        );
+    }
+}
@@ -0,0 +1,642 @@
+use crate::cli::{ItemAlignment, ItemBoundsType};
+use crate::error::{ObsCmdError, Result};
+use crate::handlers::scene_items::{fetch_item_tree, ItemNode, TreeOptions};
+use obws::common::{Alignment, BlendMode, BoundsType, MonitorType};
+use obws::requests::inputs::InputId;
+use obws::requests::scene_items::{Bounds, Crop, Position, Scale, SceneItemTransform};
+use obws::responses::scene_items::SceneItemTransform as ItemTransform;
+use obws::responses::StatusCode;
+use obws::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+
+/// Version of the state file schema written by `snapshot export`.
+///
+/// Bump it whenever a field changes meaning or a required field is added.
+pub const STATE_VERSION: u32 = 1;
+
+/// The state of an OBS instance: its scenes, inputs, transition and video
+/// settings.
+///
+/// Captured by `snapshot export`. Scenes and items are listed top to bottom,
+/// the way the OBS UI shows them, and everything except the names has a
+/// default so hand-written state files can leave most fields out.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ObsState {
+    pub version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub video: Option<VideoState>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transition: Option<TransitionState>,
+    #[serde(default)]
+    pub scenes: Vec<SceneState>,
+    #[serde(default)]
+    pub inputs: Vec<InputState>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct VideoState {
+    pub base_width: u32,
+    pub base_height: u32,
+    pub output_width: u32,
+    pub output_height: u32,
+    pub fps_numerator: u32,
+    pub fps_denominator: u32,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct TransitionState {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settings: Option<Value>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SceneState {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub filters: Vec<FilterState>,
+    #[serde(default)]
+    pub items: Vec<ItemState>,
+}
+
+/// A scene item; group items list their contents in `items`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ItemState {
+    /// Name of the input, scene or group shown by the item
+    pub source: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<u32>,
+    /// Input kind, or "scene" or "group"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub locked: bool,
+    #[serde(default)]
+    pub blend_mode: BlendModeState,
+    #[serde(default)]
+    pub transform: TransformState,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<ItemState>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct TransformState {
+    pub position_x: f32,
+    pub position_y: f32,
+    pub rotation: f32,
+    pub scale_x: f32,
+    pub scale_y: f32,
+    pub alignment: ItemAlignment,
+    pub bounds_type: ItemBoundsType,
+    pub bounds_alignment: ItemAlignment,
+    pub bounds_width: f32,
+    pub bounds_height: f32,
+    pub crop_left: u32,
+    pub crop_right: u32,
+    pub crop_top: u32,
+    pub crop_bottom: u32,
+}
+
+/// The transform OBS gives a newly added scene item
+impl Default for TransformState {
+    fn default() -> Self {
+        Self {
+            position_x: 0.0,
+            position_y: 0.0,
+            rotation: 0.0,
+            scale_x: 1.0,
+            scale_y: 1.0,
+            alignment: ItemAlignment::TopLeft,
+            bounds_type: ItemBoundsType::None,
+            bounds_alignment: ItemAlignment::Center,
+            bounds_width: 0.0,
+            bounds_height: 0.0,
+            crop_left: 0,
+            crop_right: 0,
+            crop_top: 0,
+            crop_bottom: 0,
+        }
+    }
+}
+
+impl TransformState {
+    /// The request that gives a scene item this transform. OBS rejects
+    /// bounding boxes smaller than one pixel, so unused (zero) bounds sizes
+    /// are left alone
+    pub fn request(&self) -> SceneItemTransform {
+        let bounds_size = |size: f32| (size >= 1.0).then_some(size);
+        SceneItemTransform {
+            position: Some(Position {
+                x: Some(self.position_x),
+                y: Some(self.position_y),
+            }),
+            rotation: Some(self.rotation),
+            scale: Some(Scale {
+                x: Some(self.scale_x),
+                y: Some(self.scale_y),
+            }),
+            alignment: Some(Alignment::from(self.alignment)),
+            bounds: Some(Bounds {
+                r#type: Some(BoundsType::from(self.bounds_type)),
+                alignment: Some(Alignment::from(self.bounds_alignment)),
+                width: bounds_size(self.bounds_width),
+                height: bounds_size(self.bounds_height),
+            }),
+            crop: Some(Crop {
+                left: Some(self.crop_left),
+                right: Some(self.crop_right),
+                top: Some(self.crop_top),
+                bottom: Some(self.crop_bottom),
+            }),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BlendModeState {
+    #[default]
+    Normal,
+    Additive,
+    Subtract,
+    Screen,
+    Multiply,
+    Lighten,
+    Darken,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct InputState {
+    pub name: String,
+    pub kind: String,
+    /// Settings that differ from the kind's defaults
+    #[serde(default = "empty_object")]
+    pub settings: Value,
+    /// Audio properties, for inputs that have audio
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio: Option<AudioState>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub filters: Vec<FilterState>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct AudioState {
+    /// Volume as a multiplier (1.0 is 0 dB)
+    pub volume_mul: f32,
+    pub muted: bool,
+    /// Stereo balance from 0.0 (left) to 1.0 (right)
+    pub balance: f32,
+    pub sync_offset_ms: i64,
+    pub monitor_type: MonitorTypeState,
+    /// Audio tracks (1 to 6) the input is mixed into
+    pub tracks: Vec<u8>,
+}
+
+impl Default for AudioState {
+    fn default() -> Self {
+        Self {
+            volume_mul: 1.0,
+            muted: false,
+            balance: 0.5,
+            sync_offset_ms: 0,
+            monitor_type: MonitorTypeState::None,
+            tracks: (1..=6).collect(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MonitorTypeState {
+    #[default]
+    None,
+    MonitorOnly,
+    MonitorAndOutput,
+}
+
+/// A filter of a source, in chain order.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct FilterState {
+    pub name: String,
+    pub kind: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "empty_object")]
+    pub settings: Value,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn empty_object() -> Value {
+    Value::Object(serde_json::Map::new())
+}
+
+impl From<BlendMode> for BlendModeState {
+    fn from(mode: BlendMode) -> Self {
+        match mode {
+            BlendMode::Additive => Self::Additive,
+            BlendMode::Subtract => Self::Subtract,
+            BlendMode::Screen => Self::Screen,
+            BlendMode::Multiply => Self::Multiply,
+            BlendMode::Lighten => Self::Lighten,
+            BlendMode::Darken => Self::Darken,
+            _ => Self::Normal,
+        }
+    }
+}
+
+impl From<BlendModeState> for BlendMode {
+    fn from(mode: BlendModeState) -> Self {
+        match mode {
+            BlendModeState::Normal => Self::Normal,
+            BlendModeState::Additive => Self::Additive,
+            BlendModeState::Subtract => Self::Subtract,
+            BlendModeState::Screen => Self::Screen,
+            BlendModeState::Multiply => Self::Multiply,
+            BlendModeState::Lighten => Self::Lighten,
+            BlendModeState::Darken => Self::Darken,
+        }
+    }
+}
+
+impl From<MonitorType> for MonitorTypeState {
+    fn from(monitor_type: MonitorType) -> Self {
+        match monitor_type {
+            MonitorType::MonitorOnly => Self::MonitorOnly,
+            MonitorType::MonitorAndOutput => Self::MonitorAndOutput,
+            _ => Self::None,
+        }
+    }
+}
+
+impl From<MonitorTypeState> for MonitorType {
+    fn from(monitor_type: MonitorTypeState) -> Self {
+        match monitor_type {
+            MonitorTypeState::None => Self::None,
+            MonitorTypeState::MonitorOnly => Self::MonitorOnly,
+            MonitorTypeState::MonitorAndOutput => Self::MonitorAndOutput,
+        }
+    }
+}
+
+impl From<&ItemTransform> for TransformState {
+    fn from(transform: &ItemTransform) -> Self {
+        Self {
+            position_x: transform.position_x,
+            position_y: transform.position_y,
+            rotation: transform.rotation,
+            scale_x: transform.scale_x,
+            scale_y: transform.scale_y,
+            alignment: transform.alignment.into(),
+            bounds_type: transform.bounds_type.into(),
+            bounds_alignment: transform.bounds_alignment.into(),
+            bounds_width: transform.bounds_width,
+            bounds_height: transform.bounds_height,
+            crop_left: transform.crop_left,
+            crop_right: transform.crop_right,
+            crop_top: transform.crop_top,
+            crop_bottom: transform.crop_bottom,
+        }
+    }
+}
+
+impl ObsState {
+    /// Reads a state file. Files ending in `.yaml` or `.yml` are parsed as
+    /// YAML, anything else as JSON.
+    pub fn load(path: &Path) -> Result<Self> {
+        let error = |message: String| ObsCmdError::StateFileError {
+            path: path.to_path_buf(),
+            message,
+        };
+        let text = std::fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+        let yaml = path
+            .extension()
+            .is_some_and(|ext| ext == "yaml" || ext == "yml");
+        let state: Self = if yaml {
+            serde_yaml_ng::from_str(&text).map_err(|e| error(e.to_string()))?
+        } else {
+            serde_json::from_str(&text).map_err(|e| error(e.to_string()))?
+        };
+
+        if state.version > STATE_VERSION {
+            return Err(error(format!(
+                "schema version {} is newer than the supported version {}. Update obs-cmd",
+                state.version, STATE_VERSION
+            )));
+        }
+        Ok(state)
+    }
+
+    /// Reads the live state of OBS.
+    pub async fn capture(client: &Client) -> Result<Self> {
+        let video = client
+            .config()
+            .video_settings()
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+        let transition = client
+            .transitions()
+            .current()
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+
+        let scenes = capture_scenes(client, true).await?;
+
+        let input_list = client
+            .inputs()
+            .list(None)
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?;
+        let mut inputs = Vec::with_capacity(input_list.len());
+        for input in input_list {
+            inputs.push(capture_input(client, input.id.name).await?);
+        }
+
+        Ok(Self {
+            version: STATE_VERSION,
+            video: Some(VideoState {
+                base_width: video.base_width,
+                base_height: video.base_height,
+                output_width: video.output_width,
+                output_height: video.output_height,
+                fps_numerator: video.fps_numerator,
+                fps_denominator: video.fps_denominator,
+            }),
+            transition: Some(TransitionState {
+                name: transition.id.name,
+                kind: Some(transition.kind),
+                duration_ms: transition
+                    .duration
+                    .map(|duration| duration.whole_milliseconds() as u64),
+                settings: transition.settings,
+            }),
+            scenes,
+            inputs,
+        })
+    }
+}
+
+/// Reads the scenes and their items, top to bottom. Without `details` the
+/// items only name their sources, and scene filters are left out.
+pub async fn capture_scenes(client: &Client, details: bool) -> Result<Vec<SceneState>> {
+    let mut scene_list = client
+        .scenes()
+        .list()
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?
+        .scenes;
+    // Index 0 is the bottom of the scene list in the UI
+    scene_list.sort_by_key(|scene| std::cmp::Reverse(scene.index));
+
+    let options = TreeOptions {
+        recursive: true,
+        long: details,
+        expand_nested: false,
+    };
+    let mut scenes = Vec::with_capacity(scene_list.len());
+    for scene in scene_list {
+        let name = scene.id.name;
+        let nodes =
+            fetch_item_tree(client, name.clone(), false, options, vec![name.clone()]).await?;
+        scenes.push(SceneState {
+            filters: if details {
+                capture_filters(client, &name).await?
+            } else {
+                Vec::new()
+            },
+            items: item_states(nodes),
+            name,
+        });
+    }
+    Ok(scenes)
+}
+
+/// Names of the global audio inputs (Desktop Audio, Mic/Aux) that exist
+pub async fn special_inputs(client: &Client) -> Result<Vec<String>> {
+    let specials = client
+        .inputs()
+        .specials()
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    Ok([
+        specials.desktop1,
+        specials.desktop2,
+        specials.mic1,
+        specials.mic2,
+        specials.mic3,
+        specials.mic4,
+    ]
+    .into_iter()
+    .flatten()
+    .collect())
+}
+
+/// Converts a listed item tree (bottom to top) into item states (top to
+/// bottom)
+fn item_states(nodes: Vec<ItemNode>) -> Vec<ItemState> {
+    nodes
+        .into_iter()
+        .rev()
+        .map(|node| {
+            let details = node.details;
+            ItemState {
+                source: node.source_name,
+                id: Some(node.id),
+                index: Some(node.index),
+                kind: Some(node.source_kind),
+                enabled: details.as_ref().is_none_or(|d| d.enabled),
+                locked: details.as_ref().is_some_and(|d| d.locked),
+                blend_mode: details
+                    .as_ref()
+                    .map(|d| d.blend_mode.into())
+                    .unwrap_or_default(),
+                transform: details
+                    .as_ref()
+                    .map(|d| TransformState::from(&d.transform))
+                    .unwrap_or_default(),
+                items: item_states(node.children),
+            }
+        })
+        .collect()
+}
+
+async fn capture_filters(client: &Client, source: &str) -> Result<Vec<FilterState>> {
+    let mut filters = client
+        .filters()
+        .list(source.into())
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+    filters.sort_by_key(|filter| filter.index);
+
+    Ok(filters
+        .into_iter()
+        .map(|filter| FilterState {
+            name: filter.name,
+            kind: filter.kind,
+            enabled: filter.enabled,
+            settings: filter.settings,
+        })
+        .collect())
+}
+
+async fn capture_input(client: &Client, name: String) -> Result<InputState> {
+    let id = InputId::Name(&name);
+    let settings = client
+        .inputs()
+        .settings::<Value>(id)
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+
+    Ok(InputState {
+        audio: capture_input_audio(client, &name).await?,
+        filters: capture_filters(client, &name).await?,
+        name,
+        kind: settings.kind,
+        settings: settings.settings,
+    })
+}
+
+/// Reads the mixer settings of an input, or `None` when it has no audio
+pub async fn capture_input_audio(client: &Client, name: &str) -> Result<Option<AudioState>> {
+    let id = InputId::Name(name);
+    match client.inputs().volume(id).await {
+        Ok(volume) => Ok(Some(capture_audio(client, id, volume.mul).await?)),
+        // The status obs-websocket answers with for inputs that have no audio
+        Err(obws::error::Error::Api {
+            code: StatusCode::InvalidResourceState,
+            ..
+        }) => Ok(None),
+        Err(e) => Err(ObsCmdError::ConnectionError(e)),
+    }
+}
+
+async fn capture_audio(client: &Client, id: InputId<'_>, volume_mul: f32) -> Result<AudioState> {
+    let inputs = client.inputs();
+    let tracks = inputs
+        .audio_tracks(id)
+        .await
+        .map_err(|e| ObsCmdError::ConnectionError(e))?;
+
+    Ok(AudioState {
+        volume_mul,
+        muted: inputs
+            .muted(id)
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?,
+        balance: inputs
+            .audio_balance(id)
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?,
+        sync_offset_ms: inputs
+            .audio_sync_offset(id)
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?
+            .whole_milliseconds() as i64,
+        monitor_type: inputs
+            .audio_monitor_type(id)
+            .await
+            .map_err(|e| ObsCmdError::ConnectionError(e))?
+            .into(),
+        tracks: (1..=6)
+            .filter(|track| tracks[*track as usize - 1])
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BlendModeState, ObsState, TransformState, STATE_VERSION};
+    use crate::cli::{ItemAlignment, ItemBoundsType};
+
+    #[test]
+    fn test_state_defaults() {
+        let state: ObsState = serde_json::from_str(
+            r#"{
+                "version": 1,
+                "scenes": [
+                    {"name": "Live", "items": [
+                        {"source": "Camera", "transform": {"position_x": 100.0, "alignment": "center"}},
+                        {"source": "Overlay", "enabled": false, "blend_mode": "screen"}
+                    ]}
+                ],
+                "inputs": [
+                    {"name": "Mic", "kind": "pulse_input_capture", "audio": {"volume_mul": 0.5}}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(state.version, STATE_VERSION);
+        let camera = &state.scenes[0].items[0];
+        assert!(camera.enabled);
+        assert_eq!(camera.transform.position_x, 100.0);
+        assert_eq!(camera.transform.scale_x, 1.0);
+        assert_eq!(camera.transform.alignment, ItemAlignment::Center);
+        assert_eq!(camera.transform.bounds_type, ItemBoundsType::None);
+        let overlay = &state.scenes[0].items[1];
+        assert!(!overlay.enabled);
+        assert_eq!(overlay.blend_mode, BlendModeState::Screen);
+        assert_eq!(overlay.transform, TransformState::default());
+
+        let audio = state.inputs[0].audio.as_ref().unwrap();
+        assert_eq!(audio.volume_mul, 0.5);
+        assert_eq!(audio.balance, 0.5);
+        assert_eq!(audio.tracks, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(state.inputs[0].settings, serde_json::json!({}));
+
+        let written = serde_json::to_value(&state).unwrap();
+        assert_eq!(written["scenes"][0]["items"][1]["blend_mode"], "screen");
+        assert_eq!(
+            written["scenes"][0]["items"][0]["transform"]["bounds_type"],
+            "none"
+        );
+        assert!(written.get("video").is_none());
+    }
+
+    #[test]
+    fn test_state_from_yaml() {
+        let state: ObsState = serde_yaml_ng::from_str(
+            "
+version: 1
+scenes:
+  - name: Live
+    items:
+      - source: Camera
+        transform:
+          scale_x: 0.5
+inputs:
+  - name: Camera
+    kind: v4l2_input
+    settings:
+      device: /dev/video0
+    filters:
+      - name: Color
+        kind: color_filter_v2
+        enabled: false
+",
+        )
+        .unwrap();
+
+        assert_eq!(state.scenes[0].items[0].transform.scale_x, 0.5);
+        assert_eq!(
+            state.inputs[0].settings,
+            serde_json::json!({"device": "/dev/video0"})
+        );
+        assert!(!state.inputs[0].filters[0].enabled);
+        assert!(state.inputs[0].audio.is_none());
+    }
+}
@@ -0,0 +1,185 @@
+use crate::error::{ObsCmdError, Result};
+use obws::responses::scene_items::SceneItemTransform;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Persistent obs-cmd configuration stored on the local machine.
+///
+/// The file lives at `$OBS_CMD_CONFIG` when set, otherwise at
+/// `obs-cmd/config.json` inside the platform configuration directory.
+/// Sections that this version of obs-cmd does not know about are kept
+/// untouched when the file is written back.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct UserConfig {
+    /// Named filter chains that can be applied to any source
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub filter_presets: BTreeMap<String, Vec<FilterPresetEntry>>,
+
+    /// Named scene item layouts that can be reapplied to any scene
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub layouts: BTreeMap<String, Vec<LayoutEntry>>,
+
+    /// Named OBS instances for `diff --target`, as obsws:// URLs
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub targets: BTreeMap<String, String>,
+
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// One filter of a filter preset, in chain order.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct FilterPresetEntry {
+    pub name: String,
+    pub kind: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_settings")]
+    pub settings: serde_json::Value,
+}
+
+/// The saved transform of one scene item of a layout.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct LayoutEntry {
+    /// Path of the item inside the scene, e.g. "Group/Camera"
+    pub item: String,
+    pub transform: SceneItemTransform,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_settings() -> serde_json::Value {
+    serde_json::Value::Object(serde_json::Map::new())
+}
+
+impl UserConfig {
+    /// Returns the location of the configuration file.
+    pub fn path() -> Result<PathBuf> {
+        if let Some(path) = std::env::var_os("OBS_CMD_CONFIG") {
+            return Ok(PathBuf::from(path));
+        }
+
+        let base = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .ok_or_else(|| ObsCmdError::ConfigError {
+                path: PathBuf::from("config.json"),
+                message: "could not determine the configuration directory, set OBS_CMD_CONFIG"
+                    .to_string(),
+            })?;
+
+        Ok(base.join("obs-cmd").join("config.json"))
+    }
+
+    /// Loads the configuration file, or an empty configuration if it does
+    /// not exist yet.
+    pub fn load() -> Result<Self> {
+        let path = Self::path()?;
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => {
+                return Err(ObsCmdError::ConfigError {
+                    path,
+                    message: e.to_string(),
+                })
+            }
+        };
+
+        serde_json::from_str(&contents).map_err(|e| ObsCmdError::ConfigError {
+            path,
+            message: e.to_string(),
+        })
+    }
+
+    /// Writes the configuration file, creating its directory if needed.
+    pub fn save(&self) -> Result<PathBuf> {
+        let path = Self::path()?;
+        let to_error = |e: &dyn std::fmt::Display| ObsCmdError::ConfigError {
+            path: path.clone(),
+            message: e.to_string(),
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| to_error(&e))?;
+        }
+        let contents = serde_json::to_string_pretty(self).map_err(|e| to_error(&e))?;
+        std::fs::write(&path, contents + "\n").map_err(|e| to_error(&e))?;
+
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UserConfig;
+    use obws::common::{Alignment, BoundsType};
+
+    #[test]
+    fn test_filter_presets_round_trip() {
+        let config: UserConfig = serde_json::from_str(
+            r#"{
+                "filter_presets": {
+                    "podcast-mic": [
+                        {"name": "Noise", "kind": "noise_suppress_filter_v2", "settings": {"method": "rnnoise"}},
+                        {"name": "Limiter", "kind": "limiter_filter", "enabled": false}
+                    ]
+                },
+                "targets": {"backup": "obsws://10.0.0.2:4455/secret"},
+                "future_section": {"keep": true}
+            }"#,
+        )
+        .unwrap();
+
+        let preset = &config.filter_presets["podcast-mic"];
+        assert_eq!(preset.len(), 2);
+        assert!(preset[0].enabled);
+        assert_eq!(preset[0].settings["method"], "rnnoise");
+        assert!(!preset[1].enabled);
+        assert_eq!(preset[1].settings, serde_json::json!({}));
+
+        assert_eq!(config.targets["backup"], "obsws://10.0.0.2:4455/secret");
+
+        let written = serde_json::to_value(&config).unwrap();
+        assert_eq!(written["future_section"]["keep"], true);
+        assert_eq!(
+            written["filter_presets"]["podcast-mic"][1]["kind"],
+            "limiter_filter"
+        );
+    }
+
+    #[test]
+    fn test_layouts_round_trip() {
+        let config: UserConfig = serde_json::from_str(
+            r#"{
+                "layouts": {
+                    "interview": [
+                        {"item": "Group/Host", "transform": {
+                            "sourceWidth": 1920.0, "sourceHeight": 1080.0,
+                            "positionX": 32.0, "positionY": 32.0, "rotation": 0.0,
+                            "scaleX": 0.5, "scaleY": 0.5, "width": 960.0, "height": 540.0,
+                            "alignment": 5, "boundsType": "OBS_BOUNDS_NONE",
+                            "boundsAlignment": 0, "boundsWidth": 0.0, "boundsHeight": 0.0,
+                            "cropLeft": 0, "cropRight": 0, "cropTop": 0, "cropBottom": 0
+                        }}
+                    ]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let entry = &config.layouts["interview"][0];
+        assert_eq!(entry.item, "Group/Host");
+        assert_eq!(entry.transform.position_x, 32.0);
+        assert_eq!(entry.transform.alignment, Alignment::TOP | Alignment::LEFT);
+        assert_eq!(entry.transform.bounds_type, BoundsType::None);
+
+        let written: UserConfig =
+            serde_json::from_value(serde_json::to_value(&config).unwrap()).unwrap();
+        assert_eq!(written.layouts, config.layouts);
+    }
+}